@@ -44,7 +44,7 @@ fn game_power(game: &CubeGame) -> u64 {
     }).product()
 }
 
-fn parse_line(line: &str) -> CubeGame {
+fn parse_line(line: &str) -> CubeGame<'_> {
     let (head, body) = line.split_once(": ").unwrap();
     let id = head[5..].parse().unwrap();
     let data = body.split("; ").flat_map(parse_cube_group).collect_vec();
@@ -52,7 +52,7 @@ fn parse_line(line: &str) -> CubeGame {
 
 }
 
-fn parse_cube_group(group: &str) -> Vec<CubeInfo> {
+fn parse_cube_group(group: &str) -> Vec<CubeInfo<'_>> {
     group.split(", ").map(|info| {
         let spl = info.split_once(' ').unwrap();
         (spl.0.parse().unwrap(), spl.1)
@@ -1,31 +1,68 @@
 use std::fs::read_to_string;
 
 use itertools::Itertools;
-use num::integer::lcm;
 use regex::Regex;
 use rustc_hash::FxHashMap;
 
-use crate::etc::DOUBLE_NEWLINE;
+use crate::etc::{Cycle, DOUBLE_NEWLINE};
+use crate::etc::cycle::find_cycle;
+use crate::etc::math::first_common_event;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type CaveMap<'a> = FxHashMap<&'a str, (&'a str, &'a str)>;
 
+// The steps at which a ghost is at a goal node, up to the end of
+// the first repetition of its path through the map
+struct GhostPath {
+    cycle: Cycle,
+    goal_steps: Vec<i64>,
+}
+
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day08.txt").unwrap();
     let (head, body) = input.split_once(DOUBLE_NEWLINE).unwrap();
     let map = parse(body);
 
     let sol1 = find_dist(&map, "AAA", |to| to == "ZZZ", head.chars().cycle());
-    let sol2 = map.keys()
+    let ghosts = map.keys()
         .filter(|node| node.ends_with('A'))
-        .map(|node| find_dist(&map, node, |to| to.ends_with('Z'), head.chars().cycle()))
-        .reduce(lcm).unwrap();
+        .map(|node| find_ghost_path(&map, node, head.as_bytes()))
+        .collect_vec();
+    let signals = ghosts.iter().map(|g| (g.cycle, g.goal_steps.as_slice())).collect_vec();
+    let sol2 = first_common_event(&signals).unwrap();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
+// Follows the path of a ghost until it repeats, recording when it reaches
+// a goal node. The whole list of directions must be followed before the
+// path can repeat, so only the nodes at the start of every pass through
+// the list are checked for repetitions.
+fn find_ghost_path<'a>(map: &CaveMap<'a>, from: &'a str, dirs: &[u8]) -> GhostPath {
+    let mut goal_steps = vec![];
+    let mut steps = 0;
+
+    let pass = |&node: &&'a str| -> &'a str {
+        let mut current = node;
+        for &dir in dirs {
+            if current.ends_with('Z') {
+                goal_steps.push(steps);
+            }
+            current = if dir == b'L' { map[current].0 } else { map[current].1 };
+            steps += 1;
+        }
+        current
+    };
+
+    let passes = find_cycle(from, pass);
+    let cycle = Cycle { offset: passes.offset * dirs.len(), period: passes.period * dirs.len() };
+    goal_steps.retain(|&step| step < (cycle.offset + cycle.period) as i64);
+
+    GhostPath { cycle, goal_steps }
+}
+
 fn find_dist<I, F>(map: &CaveMap, from: &str, goal: F, mut dirs: I) -> u64
 where I: Iterator<Item = char>,
       F: Fn(&str) -> bool 
@@ -44,7 +81,7 @@ where I: Iterator<Item = char>,
     steps
 }
 
-fn parse(body: &str) -> CaveMap {
+fn parse(body: &str) -> CaveMap<'_> {
     let re = Regex::new(r"(.*) = \((.*), (.*)\)").unwrap();
     body.lines()
         .map(|line| {
@@ -1,5 +1,6 @@
 use std::fs::read_to_string;
 use itertools::Itertools;
+use crate::etc::polynomial::NewtonSeries;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -19,42 +20,11 @@ pub fn solve() -> SolutionPair {
 ///////////////////////////////////////////////////////////////////////////////
 
 fn extrapolate_forwards(seq: &Seq) -> i64 {
-    let mut sequences = generate_diffs(seq);
-    let last = sequences.len() - 1;
-    sequences[last].push(0);
-
-    for i in (0..last).rev() {
-        let new = sequences[i].last().unwrap() + sequences[i+1].last().unwrap();
-        sequences[i].push(new);
-    }
-
-    *sequences[0].last().unwrap()
+    NewtonSeries::from_samples(seq).value_at(seq.len() as i64)
 }
 
 fn extrapolate_backwards(seq: &Seq) -> i64 {
-    let mut sequences = generate_diffs(seq);
-    let last = sequences.len() - 1;
-    sequences[last].insert(0, 0);
-
-    for i in (0..last).rev() {
-        let new = sequences[i][0] - sequences[i+1][0];
-        sequences[i].insert(0, new);
-    }
-
-    sequences[0][0]
-}
-
-fn generate_diffs(seq: &Seq) -> Vec<Seq> {
-    let mut sequences = vec![seq.clone()];
-    let mut last = 0;
-    
-    while !sequences[last].iter().all(|x| *x == 0) {
-        let new_seq = sequences[last].windows(2).map(|x| x[1] - x[0]).collect_vec();
-        sequences.push(new_seq);
-        last += 1;
-    }
-
-    sequences
+    NewtonSeries::from_samples(seq).value_at(-1)
 }
 
 fn parse_line(line: &str) -> Seq {
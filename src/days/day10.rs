@@ -1,7 +1,6 @@
 use std::fs::read_to_string;
-use itertools::Itertools;
 use crate::{Solution, SolutionPair};
-use crate::etc::{Coords2D, VecMat};
+use crate::etc::{Coords2D, Direction, Polygon, VecMat};
 
 ///////////////////////////////////////////////////////////////////////////////
 
@@ -12,39 +11,21 @@ pub fn solve() -> SolutionPair {
     let mut grid = VecMat::from_str(&input);
 
     let start_pos = replace_start(&mut grid);
-    let vertices = find_loop_vertices(&grid, start_pos);
+    let polygon = Polygon::new(find_loop_vertices(&grid, start_pos));
 
-    let outside = outside_points(&vertices);
-    let sol1 = outside / 2;
-    let sol2 = inside_points(&vertices, outside);
+    let sol1 = polygon.boundary_points() / 2;
+    let sol2 = polygon.interior_points();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-// https://en.wikipedia.org/wiki/Shoelace_formula
-fn total_area(vertices: &[Pos]) -> i64 {
-    vertices.iter().tuple_windows()
-        .map(|(a, b)| a.x * b.y - a.y * b.x)
-        .sum::<i64>().abs() / 2
-}
-
-pub fn outside_points(vertices: &[Pos]) -> i64 {
-    vertices.iter().tuple_windows().map(|(a, b)| a.manhattan_dist(b)).sum()
-}
-
-// https://en.m.wikipedia.org/wiki/Pick's_theorem
-pub fn inside_points(vertices: &[Pos], outside: i64) -> i64 {
-    let area = total_area(vertices);
-    area - (outside / 2) + 1
-}
-
 fn find_loop_vertices(grid: &VecMat<char>, start: Pos) -> Vec<Pos> {
     let mut dir = match grid[start] {
-        '|' | '7' | 'F' => Pos::down(),
-        'J' | 'L' => Pos::up(),
-        _ => Pos::right(),
+        '|' | '7' | 'F' => Direction::S,
+        'J' | 'L' => Direction::N,
+        _ => Direction::E,
     };
 
     let mut current = start;
@@ -59,23 +40,18 @@ fn find_loop_vertices(grid: &VecMat<char>, start: Pos) -> Vec<Pos> {
         if current == start { break }
     }
 
-    vertices.push(vertices[0]);
     vertices
 }
 
-fn next_direction(prev_direction: Pos, tile: char) -> Pos {
-    let [up, down, left, right] = Pos::origin().neighbors();
+fn next_direction(prev_direction: Direction, tile: char) -> Direction {
+    use Direction::*;
 
     match (tile, prev_direction) {
         ('|', d) | ('-', d) => d,
-        ('L', d) if d == down => right,
-        ('L', d) if d == left => up,
-        ('F', d) if d == up => right,
-        ('F', d) if d == left => down,
-        ('7', d) if d == right => down,
-        ('7', d) if d == up => left,
-        ('J', d) if d == down => left,
-        ('J', d) if d == right => up,
+        ('L', S) | ('F', N) => E,
+        ('L', W) | ('J', E) => N,
+        ('F', W) | ('7', E) => S,
+        ('7', N) | ('J', S) => W,
         _ => unreachable!()
     }
 }
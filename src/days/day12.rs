@@ -1,16 +1,18 @@
 use std::cmp::Ordering::*;
 use std::fs::read_to_string;
-use std::iter::repeat;
+use std::iter::repeat_n;
 
 use itertools::Itertools;
 use rayon::prelude::*;
-use rustc_hash::FxHashMap;
 
+use crate::etc::DenseMemo;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
-type Cache<'a> = FxHashMap<(&'a [char], &'a [usize]), u64>;
+// Every call works over suffixes of the original data and groups,
+// so their lengths are enough to identify the arguments
+type Cache = DenseMemo<u64, 2>;
 
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day12.txt").unwrap();
@@ -18,15 +20,20 @@ pub fn solve() -> SolutionPair {
     let data1 = input.lines().map(|line| parse_line(line, 1)).collect_vec();
     let data2 = input.lines().map(|line| parse_line(line, 5)).collect_vec();
     
-    let sol1: u64 = data1.par_iter().map(|(d, g)| arrangements(d, g, &mut Cache::default())).sum();
-    let sol2: u64 = data2.par_iter().map(|(d, g)| arrangements(d, g, &mut Cache::default())).sum();
+    let sol1: u64 = data1.par_iter().map(|(d, g)| count_arrangements(d, g)).sum();
+    let sol2: u64 = data2.par_iter().map(|(d, g)| count_arrangements(d, g)).sum();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-fn arrangements<'a>(data: &'a [char], groups: &'a [usize], cache: &mut Cache<'a>) -> u64 {
+fn count_arrangements(data: &[char], groups: &[usize]) -> u64 {
+    let mut cache = Cache::dense([data.len() + 1, groups.len() + 1]);
+    arrangements(data, groups, &mut cache)
+}
+
+fn arrangements(data: &[char], groups: &[usize], cache: &mut Cache) -> u64 {
     if groups.is_empty() {
         return data.iter().all(|c| *c != '#') as u64;
     }
@@ -35,21 +42,14 @@ fn arrangements<'a>(data: &'a [char], groups: &'a [usize], cache: &mut Cache<'a>
         return groups.is_empty() as u64;
     }
 
-    if let Some(&res) = cache.get(&(data, groups)) {
-        return res;
-    }
-
-    let res = match data[0] {
+    cache.get_or_compute([data.len(), groups.len()], |cache| match data[0] {
         '.' => try_skip(data, groups, cache),
         '#' => try_match(data, groups, cache),
          _  => try_skip(data, groups, cache) + try_match(data, groups, cache)
-    };
-
-    cache.insert((data, groups), res);
-    res
+    })
 }
 
-fn try_skip<'a>(data: &'a [char], groups: &'a [usize], cache: &mut Cache<'a>) -> u64 {
+fn try_skip(data: &[char], groups: &[usize], cache: &mut Cache) -> u64 {
     let can_skip = groups.is_empty() || data.len() > groups.iter().sum::<usize>() + (groups.len() - 1);
 
     if can_skip {
@@ -57,7 +57,7 @@ fn try_skip<'a>(data: &'a [char], groups: &'a [usize], cache: &mut Cache<'a>) ->
     } else { 0 }
 }
 
-fn try_match<'a>(data: &'a [char], groups: &'a [usize], cache: &mut Cache<'a>) -> u64 {
+fn try_match(data: &[char], groups: &[usize], cache: &mut Cache) -> u64 {
     let group_len = groups[0];
     let can_start_match = match data.len().cmp(&group_len) {
         Less => false,
@@ -75,8 +75,8 @@ fn try_match<'a>(data: &'a [char], groups: &'a [usize], cache: &mut Cache<'a>) -
 fn parse_line(line: &str, repeats: usize) -> (Vec<char>, Vec<usize>) {
     let (left, right) = line.split_once(' ').unwrap();
 
-    let chars = repeat(left).take(repeats).join("?");
-    let counts = repeat(right).take(repeats).join(",");
+    let chars = repeat_n(left, repeats).join("?");
+    let counts = repeat_n(right, repeats).join(",");
 
     let chars_vec = chars.chars().collect_vec();
     let counts_vec = counts.split(',').map(|x| x.parse().unwrap()).collect_vec();
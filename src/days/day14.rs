@@ -1,49 +1,38 @@
 use std::fs::read_to_string;
-use rustc_hash::FxHashMap;
-use crate::etc::{VecMat, Coords2D};
+use crate::etc::{Automaton, VecMat, Coords2D};
+use crate::etc::animation::FrameRecorder;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
 type Grid = VecMat<char>;
 type Pos = Coords2D<i32>;
-const PART_2: u64 = 1_000_000_000;
+const PART_2: usize = 1_000_000_000;
 
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day14.txt").unwrap();
     let mut grid1 = VecMat::from_str(&input);
-    let mut grid2 = grid1.clone();
+    let grid2 = grid1.clone();
 
     slide_up(&mut grid1);
     let sol1 = calculate_load(&grid1);
 
-    let (offset, cycle_len) = find_repetition(&mut grid2.clone());
-    let n = offset + (PART_2 - offset) % cycle_len;
+    let mut recorder = FrameRecorder::from_env("day14");
+    recorder.push_text(&grid2);
 
-    for _ in 0..n {
-        cycle(&mut grid2);
-    }
-    let sol2 = calculate_load(&grid2);
+    let mut automaton = Automaton::new(grid2, calculate_load);
+    automaton.advance_to(PART_2, |grid| {
+        cycle(grid);
+        recorder.push_text(grid);
+    });
+    recorder.save_requested().unwrap();
+    let sol2 = calculate_load(automaton.grid());
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-fn find_repetition(grid: &mut Grid) -> (u64, u64) {
-    let mut i = 0;
-    let mut memory = FxHashMap::default();
-
-    loop {
-        if let Some(&prev) = memory.get(grid) {
-            break (prev, i - prev);
-        }
-        memory.insert(grid.clone(), i);
-        cycle(grid);
-        i += 1;
-    }
-}
-
 fn cycle(grid: &mut Grid) {
     for _ in 0..4 {
         slide_up(grid);
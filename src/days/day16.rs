@@ -4,7 +4,7 @@ use itertools::Itertools;
 use rayon::prelude::*;
 use rustc_hash::FxHashSet;
 
-use crate::etc::{VecMat, Coords2D};
+use crate::etc::{VecMat, Coords2D, Direction};
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -15,7 +15,7 @@ pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day16.txt").unwrap();
     let grid = VecMat::from_str(&input);
 
-    let sol1 = project_ray(&grid, Pos::origin(), Pos::right());
+    let sol1 = project_ray(&grid, Pos::origin(), Direction::E);
     let sol2 = find_best(&grid);
 
     (Solution::from(sol1), Solution::from(sol2))
@@ -27,10 +27,10 @@ fn find_best(grid: &VecMat<char>) -> usize {
     let (w, h) = (grid.width() as i32, grid.height() as i32);
 
     let iters = [
-        (0..w, 0..1, Pos::down()),
-        (0..w, h-1..h, Pos::up()),
-        (0..1, 0..h, Pos::right()),
-        (w-1..w, 0..h, Pos::left()),
+        (0..w, 0..1, Direction::S),
+        (0..w, h-1..h, Direction::N),
+        (0..1, 0..h, Direction::E),
+        (w-1..w, 0..h, Direction::W),
     ];
 
     // Builds a vec with all starting positions along the borders
@@ -42,7 +42,7 @@ fn find_best(grid: &VecMat<char>) -> usize {
     starts.into_par_iter().map(|(start, dir)| project_ray(grid, start, dir)).max().unwrap()
 }
 
-fn project_ray(grid: &VecMat<char>, start_pos: Pos, start_dir: Pos) -> usize {
+fn project_ray(grid: &VecMat<char>, start_pos: Pos, start_dir: Direction) -> usize {
     let mut heads = vec![(start_pos, start_dir)];
     let mut visited = FxHashSet::default();
 
@@ -66,17 +66,15 @@ fn project_ray(grid: &VecMat<char>, start_pos: Pos, start_dir: Pos) -> usize {
     visited.into_iter().map(|(pos, _)| pos).unique().count()
 }
 
-fn next_directions(current: char, prev_dir: Pos) -> Vec<Pos> {
+fn next_directions(current: char, prev_dir: Direction) -> Vec<Direction> {
+    use Direction::*;
+
     match (current, prev_dir) {
-        ('.', d) => vec![d],
-        ('|', d) if d.x == 0 => vec![d],
-        ('|', _) => vec![Pos::up(), Pos::down()],
-        ('-', d) if d.y == 0 => vec![d],
-        ('-', _) => vec![Pos::left(), Pos::right()],
-        ('/', Pos{x: 0, y}) => vec![(-y, 0).into()],
-        ('/', Pos{x, ..}) => vec![(0, -x).into()],
-        ('\\', Pos{x: 0, y}) => vec![(y, 0).into()],
-        ('\\', Pos{x, ..}) => vec![(0, x).into()],
+        ('.', d) | ('|', d @ (N | S)) | ('-', d @ (E | W)) => vec![d],
+        ('|', _) => vec![N, S],
+        ('-', _) => vec![E, W],
+        ('/', d @ (N | S)) | ('\\', d @ (E | W)) => vec![d.turn_right()],
+        ('/', d) | ('\\', d) => vec![d.turn_left()],
         _ => unreachable!()
     }
 }
@@ -1,6 +1,6 @@
 use std::fs::read_to_string;
-use pathfinding::directed::astar::astar;
-use crate::etc::{Coords2D, VecMat};
+use crate::etc::{Coords2D, VecMat, SearchProblem};
+use crate::etc::search::astar;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -14,6 +14,13 @@ struct State {
     repeats: u8,
 }
 
+struct Crucible<'a> {
+    grid: &'a VecMat<u32>,
+    goal: Pos,
+    min_repeats: u8,
+    max_repeats: u8,
+}
+
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day17.txt").unwrap();
     let grid = VecMat::map_from_str(&input, |x| x.to_digit(10).unwrap());
@@ -28,31 +35,42 @@ pub fn solve() -> SolutionPair {
 
 fn find_best_path(grid: &VecMat<u32>, ultra: bool) -> u32 {
     let goal = Pos::new(grid.width() as i32 - 1, grid.height() as i32 - 1);
+    let (min_repeats, max_repeats) = if ultra { (4, 10) } else { (1, 3) };
+    let crucible = Crucible { grid, goal, min_repeats, max_repeats };
 
-    astar(
-        &State { pos: Pos::origin(), prev_dir: Pos::origin(), repeats: 1 },
-        |state| available_steps(state, grid, ultra),
-        |state| state.pos.manhattan_dist(&goal) as u32,
-        |state| state.pos == goal
-    ).unwrap().1
+    astar(&crucible).cost().unwrap()
 }
 
-fn available_steps(state: &State, grid: &VecMat<u32>, ultra: bool) -> Vec<(State, u32)> {
-    let (min_repeats, max_repeats) = if ultra { (4, 10) } else { (1, 3) };
+impl SearchProblem for Crucible<'_> {
+    type State = State;
+    type Cost = u32;
+
+    fn start(&self) -> State {
+        State { pos: Pos::origin(), prev_dir: Pos::origin(), repeats: 1 }
+    }
+
+    fn successors(&self, state: &State) -> Vec<(State, u32)> {
+        self.grid.neighbors4(state.pos)
+            .map(|(new_pos, cost)| (new_pos, new_pos - state.pos, cost))
+            .filter(|&(_, new_dir, _)| {
+                // Force a minimum amount of direction repetitions
+                // (only applies to part 2, and if not in the starting point)
+                (state.repeats >= self.min_repeats || new_dir == state.prev_dir || state.prev_dir == Pos::origin()) &&
+                new_dir != -state.prev_dir && // Don't backtrack
+                !(state.repeats >= self.max_repeats && new_dir == state.prev_dir) // Don't repeat directions
+            })
+            .map(|(new_pos, new_dir, cost)| {
+                let repeats = if new_dir != state.prev_dir { 1 } else { state.repeats + 1 };
+                let state = State { pos: new_pos, prev_dir: new_dir, repeats };
+                (state, cost)
+            }).collect()
+    }
+
+    fn is_goal(&self, state: &State) -> bool {
+        state.pos == self.goal
+    }
 
-    state.pos.neighbors().into_iter()
-        .map(|new_pos| (new_pos, new_pos - state.pos))
-        .filter(|&(new_pos, new_dir)| {
-            // Force a minimum amount of direction repetitions
-            // (only applies to part 2, and if not in the starting point)
-            (state.repeats >= min_repeats || new_dir == state.prev_dir || state.prev_dir == Pos::origin()) &&
-            new_dir != -state.prev_dir && // Don't backtrack
-            grid.is_in_bounds(new_pos) && // Don't go OOB
-            !(state.repeats >= max_repeats && new_dir == state.prev_dir) // Don't repeat directions
-        })
-        .map(|(new_pos, new_dir)| {
-            let repeats = if new_dir != state.prev_dir { 1 } else { state.repeats + 1 };
-            let state = State { pos: new_pos, prev_dir: new_dir, repeats };
-            (state, grid[new_pos])
-        }).collect()
+    fn heuristic(&self, state: &State) -> u32 {
+        state.pos.manhattan_dist(&self.goal) as u32
+    }
 }
@@ -1,8 +1,7 @@
 use std::fs::read_to_string;
 use itertools::Itertools;
 use sscanf::sscanf;
-use crate::etc::Coords2D;
-use crate::days::day10;
+use crate::etc::{Coords2D, Direction, Polygon};
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -23,30 +22,9 @@ pub fn solve() -> SolutionPair {
 
 ///////////////////////////////////////////////////////////////////////////////
 
-fn calculate_points(instrs: &[Instr]) -> i64 {
-    let vertices = find_vertices(instrs);
-    let outside = day10::outside_points(&vertices);
-    let inside = day10::inside_points(&vertices, outside);
-    outside + inside
-}
-
-fn find_vertices(instrs: &[Instr]) -> Vec<Pos> {
-    let mut current = Pos::origin();
-    let mut res = vec![current];
-
-    for &(dir, amt, _) in instrs {
-        let delta = match dir {
-            'U' => Pos::up(),
-            'D' => Pos::down(),
-            'L' => Pos::left(),
-             _  => Pos::right(),
-        };
-
-        current += delta * amt;
-        res.push(current);
-    }
-
-    res
+fn calculate_points(instrs: &[Instr]) -> i128 {
+    let moves = instrs.iter().map(|&(dir, amt, _)| (Direction::from_char(dir).unwrap(), amt));
+    Polygon::from_moves(Pos::origin(), moves).lattice_points()
 }
 
 fn true_instruction<'a>(instr: &Instr<'a>) -> Instr<'a> {
@@ -60,6 +38,6 @@ fn true_instruction<'a>(instr: &Instr<'a>) -> Instr<'a> {
     (dir, number, "")
 }
 
-fn parse_line(line: &str) -> Instr {
+fn parse_line(line: &str) -> Instr<'_> {
     sscanf!(line, "{char} {i64} (#{str})").unwrap()
 }
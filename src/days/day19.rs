@@ -129,7 +129,7 @@ fn get_attr(piece: &Piece, attr: char) -> i64 {
     }
 }
 
-fn parse_workflow(line: &str) -> (&str, Vec<Rule>) {
+fn parse_workflow(line: &str) -> (&str, Vec<Rule<'_>>) {
     let (label, rest) = line.split_once('{').unwrap();
     let body = rest.trim_end_matches('}').split(',')
         .map(parse_rule)
@@ -138,7 +138,7 @@ fn parse_workflow(line: &str) -> (&str, Vec<Rule>) {
     (label, body)
 }
 
-fn parse_rule(text: &str) -> Rule {
+fn parse_rule(text: &str) -> Rule<'_> {
     if !text.contains(':') {
         Rule { target: text, cond: Condition::Always }
     } else {
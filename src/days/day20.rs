@@ -1,31 +1,43 @@
 use std::fs::read_to_string;
 
 use itertools::Itertools;
-use num::integer::lcm;
-use rustc_hash::FxHashMap;
 
+use crate::etc::{Cycle, StrInterner};
+use crate::etc::cycle::find_cycle_by_key;
+use crate::etc::math::first_common_event;
 use crate::{Solution, SolutionPair};
 use Module::*;
 
 ///////////////////////////////////////////////////////////////////////////////
 
-type ModuleMap<'a> = FxHashMap<&'a str, Module<'a>>;
-type TargetedPulse<'a> = (bool, &'a str, &'a str);
+type ModuleId = usize;
+type ModuleMap = Vec<Option<Module>>;
+type TargetedPulse = (bool, ModuleId, ModuleId);
 
-enum Module<'a> {
+// The presses at which a module sends a high pulse, up to the end of
+// the first repetition of the state of the modules that feed it
+struct Firings {
+    cycle: Cycle,
+    presses: Vec<i64>,
+}
+
+// The broadcaster is interned before anything else
+const BROADCASTER: ModuleId = 0;
+
+enum Module {
     Conjunction { 
-        input_labels: Vec<&'a str>,
+        input_labels: Vec<ModuleId>,
         state: Vec<bool>,
-        outputs: Vec<&'a str>
+        outputs: Vec<ModuleId>
     },
 
     FlipFlop {
         state: bool,
-        outputs: Vec<&'a str>
+        outputs: Vec<ModuleId>
     },
 
     Broadcast {
-        outputs: Vec<&'a str>
+        outputs: Vec<ModuleId>
     }    
 }
 
@@ -33,32 +45,86 @@ enum Module<'a> {
 
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day20.txt").unwrap();
-    let mut modules: ModuleMap = input.lines().map(parse_line).collect();
-    for line in input.lines() {
-        update_inputs(line, &mut modules);
+    let mut names = StrInterner::default();
+    names.get_id("broadcaster");
+
+    let parsed = input.lines().map(|line| parse_line(line, &mut names)).collect_vec();
+    let mut modules: ModuleMap = (0..names.len()).map(|_| None).collect();
+    for (id, module) in parsed {
+        modules[id] = Some(module);
     }
+    update_inputs(&mut modules);
 
     let sol1 = count_pulses(&mut modules);
-    let labels_rx = find_rx_components(&modules);
-    let sol2 = labels_rx.into_iter()
-        .map(|label| find_on_cycle(&mut modules, label))
-        .reduce(lcm).unwrap();
+    let labels_rx = find_rx_components(&modules, names.get("rx").unwrap());
+    let firings = labels_rx.into_iter()
+        .map(|label| find_firing_cycle(&mut modules, label))
+        .collect_vec();
+    let signals = firings.iter().map(|f| (f.cycle, f.presses.as_slice())).collect_vec();
+    let sol2 = first_common_event(&signals).unwrap();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-fn find_on_cycle(modules: &mut ModuleMap, label: &str) -> u64 {
+// Presses the button until the state of the sub-circuit that feeds a module
+// repeats, recording the presses that make the module send a high pulse.
+// The rest of the modules can't affect that sub-circuit, so its state alone
+// determines when the module fires.
+fn find_firing_cycle(modules: &mut ModuleMap, label: ModuleId) -> Firings {
     reset_modules(modules);
-    let mut i = 0;
+    let upstream = upstream_modules(modules, label);
+    let mut presses = vec![];
+    let mut press = 0;
+
+    let start = (circuit_state(modules, &upstream), false);
+    let cycle = find_cycle_by_key(start, |_| {
+        press += 1;
+        let fired = push_button(modules, Some(label)).2;
+        if fired {
+            presses.push(press);
+        }
+        (circuit_state(modules, &upstream), fired)
+    }, |(state, _)| state.clone());
+
+    // Press i happens in state i - 1, so shifting the cycle by one makes
+    // it describe the presses instead of the states they start from
+    let cycle = Cycle { offset: cycle.offset + 1, period: cycle.period };
+    presses.retain(|&press| press < (cycle.offset + cycle.period) as i64);
+    Firings { cycle, presses }
+}
 
-    loop {
-        i += 1;
-        if (0, 0) == push_button(modules, Some(label)) {
-            break i;
+// All the modules that can send pulses to a module, directly or
+// indirectly, including itself
+fn upstream_modules(modules: &ModuleMap, label: ModuleId) -> Vec<ModuleId> {
+    let mut found = vec![false; modules.len()];
+    let mut pending = vec![label];
+    found[label] = true;
+
+    while let Some(current) = pending.pop() {
+        for (id, module) in modules.iter().enumerate() {
+            if let Some(module) = module {
+                if !found[id] && module.outputs().contains(&current) {
+                    found[id] = true;
+                    pending.push(id);
+                }
+            }
         }
     }
+
+    (0..modules.len()).filter(|&id| found[id]).collect()
+}
+
+fn circuit_state(modules: &ModuleMap, circuit: &[ModuleId]) -> Vec<bool> {
+    circuit.iter()
+        .filter_map(|&id| modules[id].as_ref())
+        .flat_map(|module| match module {
+            Conjunction { state, .. } => state.clone(),
+            FlipFlop { state, .. } => vec![*state],
+            Broadcast { .. } => vec![],
+        })
+        .collect()
 }
 
 fn count_pulses(modules: &mut ModuleMap) -> u64 {
@@ -73,8 +139,8 @@ fn count_pulses(modules: &mut ModuleMap) -> u64 {
     lo * hi
 }
 
-fn find_rx_components<'a>(modules: &ModuleMap<'a>) -> Vec<&'a str> {
-    let module = modules.values().find(|m| m.outputs().contains(&"rx")).unwrap();
+fn find_rx_components(modules: &ModuleMap, rx: ModuleId) -> Vec<ModuleId> {
+    let module = modules.iter().flatten().find(|m| m.outputs().contains(&rx)).unwrap();
     if let Conjunction{input_labels, ..} = module {
         return input_labels.clone();
     }
@@ -83,7 +149,7 @@ fn find_rx_components<'a>(modules: &ModuleMap<'a>) -> Vec<&'a str> {
 }
 
 fn reset_modules(modules: &mut ModuleMap) {
-    for module in modules.values_mut() {
+    for module in modules.iter_mut().flatten() {
         if let Conjunction{state, ..} = module {
             state.iter_mut().for_each(|x| *x = false);
         } else if let FlipFlop{state, ..} = module {
@@ -92,11 +158,14 @@ fn reset_modules(modules: &mut ModuleMap) {
     }
 }
 
-fn push_button(modules: &mut ModuleMap, break_on: Option<&str>) -> (u64, u64) {
+// Processes all the pulses caused by a button press, returning the amount of
+// low and high pulses, and whether the watched module sent a high pulse
+fn push_button(modules: &mut ModuleMap, watch: Option<ModuleId>) -> (u64, u64, bool) {
     let mut lo = 1;
     let mut hi = 0;
+    let mut watched_high = false;
 
-    let mut to_process = modules["broadcaster"].emit_pulse();
+    let mut to_process = modules[BROADCASTER].as_ref().unwrap().emit_pulse(BROADCASTER);
 
     while !to_process.is_empty() {
         let mut new_to_process = vec![];
@@ -104,11 +173,11 @@ fn push_button(modules: &mut ModuleMap, break_on: Option<&str>) -> (u64, u64) {
         for (pulse, from, to) in to_process {
             if pulse { hi += 1} else { lo += 1 };
 
-            if pulse && break_on == Some(from) {
-                return (0, 0);
+            if pulse && watch == Some(from) {
+                watched_high = true;
             }
 
-            if let Some(recv) = modules.get_mut(to) {
+            if let Some(recv) = &mut modules[to] {
                 new_to_process.extend(recv.accept_pulse(pulse, from, to));
             }
         }
@@ -116,45 +185,46 @@ fn push_button(modules: &mut ModuleMap, break_on: Option<&str>) -> (u64, u64) {
         to_process = new_to_process;
     }
 
-    (lo, hi)
+    (lo, hi, watched_high)
 }
 
-fn parse_line(line: &str) -> (&str, Module) {
+fn parse_line<'a>(line: &'a str, names: &mut StrInterner<'a>) -> (ModuleId, Module) {
     let (head, body) = line.split_once(" -> ").unwrap();
-    let outputs = body.split(", ").collect_vec();
+    let outputs = body.split(", ").map(|label| names.get_id(label)).collect_vec();
 
     if let Some(label) = head.strip_prefix('%') {
-        (label, FlipFlop { state: false, outputs })
+        (names.get_id(label), FlipFlop { state: false, outputs })
     } else if let Some(label) = head.strip_prefix('&') {
-        (label, Conjunction { input_labels: vec![], state: vec![], outputs })
+        (names.get_id(label), Conjunction { input_labels: vec![], state: vec![], outputs })
     } else {
-        (head, Broadcast { outputs })
+        (names.get_id(head), Broadcast { outputs })
     }
 }
 
-fn update_inputs<'a>(line: &'a str, modules: &mut ModuleMap<'a>) {
-    let (head, body) = line.split_once(" -> ").unwrap();
-    let label = head.strip_prefix(['&', '%']).unwrap_or(head);
-    let outputs = body.split(", ").collect_vec();
+fn update_inputs(modules: &mut ModuleMap) {
+    let connections = modules.iter().enumerate()
+        .filter_map(|(id, module)| module.as_ref().map(|m| (id, m)))
+        .flat_map(|(id, module)| module.outputs().iter().map(move |&out| (id, out)))
+        .collect_vec();
 
-    for out_label in outputs {
-        if let Some(Conjunction{input_labels, state, ..}) = modules.get_mut(out_label) {
+    for (label, out_label) in connections {
+        if let Some(Conjunction{input_labels, state, ..}) = &mut modules[out_label] {
             input_labels.push(label);
             state.push(false);
         }
     }
 }
 
-impl<'a> Module<'a> {
-    fn emit_pulse<'b>(&'b self) -> Vec<TargetedPulse<'a>> {
+impl Module {
+    fn emit_pulse(&self, this: ModuleId) -> Vec<TargetedPulse> {
         if let Broadcast { outputs } = self {
-            return outputs.iter().copied().map(|label| (false, "broadcast", label)).collect();
+            return outputs.iter().copied().map(|label| (false, this, label)).collect();
         }
 
         unreachable!()
     }
 
-    fn accept_pulse<'b>(&'b mut self, pulse: bool, from: &'a str, this: &'a str) -> Vec<TargetedPulse<'a>> {
+    fn accept_pulse(&mut self, pulse: bool, from: ModuleId, this: ModuleId) -> Vec<TargetedPulse> {
         match self {
             Conjunction { input_labels, state, outputs } => {
                 let ix = input_labels.iter().position(|x| *x == from).unwrap();
@@ -174,7 +244,7 @@ impl<'a> Module<'a> {
         }
     }
 
-    fn outputs(&self) -> &[&str] {
+    fn outputs(&self) -> &[ModuleId] {
         match self {
             Conjunction { outputs, .. } => outputs,
             FlipFlop { outputs, .. } => outputs,
@@ -1,85 +1,57 @@
 use std::fs::read_to_string;
 
-use eqsolver::multivariable::MultiVarNewton;
-use itertools::Itertools;
-use nalgebra::{Vector3, Matrix3};
-use rustc_hash::FxHashSet;
-
-use crate::etc::{Coords2D, VecMat};
+use crate::etc::{Automaton, BitGrid, Coords2D, TiledGrid, VecMat};
+use crate::etc::polynomial::NewtonSeries;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
-const PART2_STEPS: f64 = 26_501_365.;
+const PART2_STEPS: usize = 26_501_365;
 type Pos = Coords2D<i32>;
 
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day21.txt").unwrap();
     let mut grid = VecMat::from_str(&input);
     let start = find_replace_start(&mut grid);
+    let garden = TiledGrid::repeating(grid);
 
-    let reachable_in_steps = do_steps(&grid, 327, start);
+    let size = garden.tile_width();
+    let reachable_in_steps = do_steps(&garden, PART2_STEPS % size + 2 * size, start);
 
     let sol1 = reachable_in_steps[64];
-
-    let (a, b, c) = find_quadratic_terms(&reachable_in_steps);
-    let sol2 = (a * PART2_STEPS.powi(2) + b * PART2_STEPS + c) as u64;
+    let sol2 = extrapolate_reachable(&reachable_in_steps, size);
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
-fn find_quadratic_terms(reachable: &[u64]) -> (f64, f64, f64) {
-    // Semi-general solver for part 2. It's built upon the magical properties
-    // of the actual input and the fact that you can solve it by extrapolating
-    // the quadratic sequence of steps to reachable tiles, but it attempts to
-    // find the specific quadratic terms of the provided input instead of just
-    // solving the specific equation for my specific input.
-    // It uses the amount of reachable tiles at 65, 196 and 327 steps
-    // to figure out the generic formula for the input.
-
-    let f = |v: Vector3<f64>| Vector3::new(v[0] *   65.*65. + v[1] *  65. + v[2] -  reachable[65] as f64,
-                                           v[0] * 196.*196. + v[1] * 196. + v[2] - reachable[196] as f64,
-                                           v[0] * 327.*327. + v[1] * 327. + v[2] - reachable[327] as f64);
-
-    // Props to this website because i had no clue about what a jacobian matrix
-    // is or how to calculate it 🤡
-    // https://www.allmath.com/jacobian-matrix-calculator.php
-    let j = |_: Vector3<f64>| Matrix3::new(  65.*65.,  65., 1.,
-                                           196.*196., 196., 1.,
-                                           327.*327., 327., 1.);
-
-    let solution = MultiVarNewton::new(f, j)
-                .solve(Vector3::new(1., 1., 1.))
-                .unwrap();
-
-    solution.column(0).into_iter().copied().collect_tuple().unwrap()
+// Semi-general solver for part 2. It's built upon the magical properties
+// of the actual input, where the amount of reachable tiles grows quadratically
+// every time the steps reach a new copy of the garden. The amounts at 65, 196
+// and 327 steps (for a garden of size 131) are samples of that quadratic
+// sequence, so it can be extrapolated exactly to the amount of steps needed.
+fn extrapolate_reachable(reachable: &[i64], size: usize) -> i64 {
+    let offset = PART2_STEPS % size;
+    let samples = [0, 1, 2].map(|i| reachable[offset + i * size]);
+    let index = (PART2_STEPS / size) as i64;
+    NewtonSeries::from_samples(&samples).value_at(index)
 }
 
-fn do_steps(grid: &VecMat<char>, n_steps: usize, start: Pos) -> Vec<u64> {
-    let mut to_visit = FxHashSet::default();
-    to_visit.insert(start);
-    let mut reachable = vec![1];
-
-    for _ in 0..n_steps {
-        let mut new_to_visit = FxHashSet::default();
+fn do_steps(garden: &TiledGrid<char>, n_steps: usize, start: Pos) -> Vec<i64> {
+    // Lay out enough copies of the garden around the starting one
+    // so that the reachable area never touches the edges
+    let (w, h) = (garden.tile_width() as i32, garden.tile_height() as i32);
+    let reps = n_steps as i32 / w.min(h) + 1;
+    let side = (2 * reps + 1) as usize;
+    let area = garden.to_vecmat(Pos::new(-reps * w, -reps * h), side * w as usize, side * h as usize);
 
-        for pos in to_visit {
-            new_to_visit.extend(
-                pos.neighbors().iter().filter(|&n| get_tile_mirror(grid, *n) == '.')
-            );
-        }
+    let open = BitGrid::from_vecmat(&area, |ch| ch == '.');
+    let mut start_grid = BitGrid::new(open.width(), open.height());
+    start_grid.set(start + Pos::new(reps * w, reps * h), true);
 
-        to_visit = new_to_visit;
-        reachable.push(to_visit.len() as u64);
-    }
-
-    reachable
-}
+    let mut automaton = Automaton::new(start_grid, BitGrid::count_ones);
+    automaton.run(n_steps, |current| &current.adjacent4() & &open);
 
-fn get_tile_mirror(grid: &VecMat<char>, pos: Pos) -> char {
-    let x = pos.x.rem_euclid(grid.width() as i32);
-    let y = pos.y.rem_euclid(grid.height() as i32);
-    grid[(x, y)]
+    automaton.populations().iter().map(|&count| count as i64).collect()
 }
 
 fn find_replace_start(grid: &mut VecMat<char>) -> Pos {
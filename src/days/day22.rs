@@ -2,25 +2,21 @@ use std::fs::read_to_string;
 use std::cmp::min;
 
 use itertools::Itertools;
-use petgraph::prelude::{Graph, NodeIndex, Direction, EdgeRef};
-use rustc_hash::{FxHashMap, FxHashSet};
-use sscanf::{sscanf, FromScanf};
+use rustc_hash::FxHashMap;
 
+use crate::etc::{Coords3D, LabeledGraph};
+use crate::etc::graph::dominated_counts;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
+type Pos3D = Coords3D<i32>;
 type PosMap = FxHashMap<Pos3D, usize>;
-type SupportGraph = Graph<(), ()>;
+type SupportGraph = LabeledGraph<usize, ()>;
 
-#[derive(PartialEq, Eq, Hash, FromScanf)]
-#[sscanf(format = "{x},{y},{z}")]
-struct Pos3D {
-    x: i32, y: i32, z: i32
-}
+// Label of the node that supports all the blocks lying on the floor
+const GROUND: usize = usize::MAX;
 
-#[derive(FromScanf)]
-#[sscanf(format = "{start}~{end}")]
 struct Block {
     start: Pos3D,
     end: Pos3D
@@ -34,67 +30,37 @@ pub fn solve() -> SolutionPair {
 
     simulate_fall(&mut blocks, &mut occupied);
     let graph = build_support_graph(&blocks, &occupied);
-    let essentials = essential_blocks(&graph);
 
-    let sol1 = blocks.len() - essentials.len();
-    
-    let sol2: usize = essentials.into_iter()
-        .map(|node| all_dependent_blocks(node, &graph))
-        .sum();
+    // Removing a block makes another one fall exactly when every chain of
+    // supports from the ground to the latter goes through the former,
+    // that is, when the removed block dominates it in the support graph
+    let ground = graph.index_of(&GROUND).unwrap();
+    let falling = dominated_counts(graph.graph(), ground);
+    let block_counts = falling.iter()
+        .filter(|(&node, _)| node != ground)
+        .map(|(_, &count)| count)
+        .collect_vec();
+
+    let sol1 = block_counts.iter().filter(|&&count| count == 0).count();
+    let sol2: usize = block_counts.iter().sum();
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
-fn essential_blocks(graph: &SupportGraph) -> FxHashSet<NodeIndex> {
-    let mut res = FxHashSet::default();
-
-    for node in graph.node_indices() {
-        let edges = graph.edges_directed(node, Direction::Incoming).collect_vec();
-        if edges.len() == 1 {
-            res.insert(edges[0].source());
-        }
-    }
-    
-    res
-}
-
-fn all_dependent_blocks(node: NodeIndex, graph: &SupportGraph) -> usize {
-    let mut fallen = FxHashSet::default();
-    fallen.insert(node);
-    let mut to_visit: FxHashSet<_> = graph.neighbors(node).collect();
-
-    while !to_visit.is_empty() {
-        let mut new_to_visit = FxHashSet::default();
-
-        for new_node in to_visit {
-            // This node falls if all its supporting nodes have fallen
-            let falls = graph.neighbors_directed(new_node, Direction::Incoming)
-                .all(|n| fallen.contains(&n));
-            if falls {
-                fallen.insert(new_node);
-                new_to_visit.extend(graph.neighbors(new_node));
-            }
-        }
-
-        to_visit = new_to_visit;
-    }
-
-    fallen.len() - 1
-}
-
 fn build_support_graph(blocks: &[Block], occupied: &PosMap) -> SupportGraph {
-    let mut id_map = FxHashMap::default();
     let mut graph = SupportGraph::new();
+    graph.node(GROUND);
 
     for (i, block) in blocks.iter().enumerate() {
-        let this_ix = *id_map.entry(i).or_insert(graph.add_node(()));
-        let supporting_blocks = block.bottom_hitboxes().filter_map(|pos| occupied.get(&pos)).unique();
+        graph.node(i);
+        if block.bottom_hitboxes().all(|pos| pos.z < 1) {
+            graph.add_edge(GROUND, i, ());
+        }
 
-        for &supp in supporting_blocks {
-            let supp_ix = *id_map.entry(supp).or_insert(graph.add_node(()));
-            graph.add_edge(supp_ix, this_ix, ());
+        for &supp in block.bottom_hitboxes().filter_map(|pos| occupied.get(&pos)).unique() {
+            graph.add_edge(supp, i, ());
         }
     }
 
@@ -115,7 +81,8 @@ fn simulate_fall(blocks: &mut [Block], occupied: &mut PosMap) {
 
 impl Block {
     fn from_line(line: &str) -> Self {
-        sscanf!(line, "{Block}").unwrap()
+        let (start, end) = line.split_once('~').unwrap();
+        Self { start: start.parse().unwrap(), end: end.parse().unwrap() }
     }
 
     fn bottom_hitboxes(&self) -> impl Iterator<Item = Pos3D> + '_ {
@@ -123,7 +90,7 @@ impl Block {
 
         (self.start.x ..= self.end.x).flat_map(move |x| {
             (self.start.y ..= self.end.y).map(move |y| {
-                Pos3D { x, y, z: lower_z }
+                Pos3D::new(x, y, lower_z)
             })
         })
     }
@@ -132,7 +99,7 @@ impl Block {
         (self.start.x ..= self.end.x).flat_map(move |x| {
             (self.start.y ..= self.end.y).flat_map(move |y| {
                 (self.start.z ..= self.end.z).map(move |z| {
-                    Pos3D { x, y, z }
+                    Pos3D::new(x, y, z)
                 })
             })
         })
@@ -1,21 +1,20 @@
 use std::fs::read_to_string;
 use std::ops::RangeInclusive;
 
-use rayon::prelude::*;
+use num::BigRational;
+use num::rational::Ratio;
+use num_traits::ToPrimitive;
 use itertools::Itertools;
 
-use crate::etc::Coords2D;
+use crate::etc::{Axis, Coords3D, Intersection, Ray};
+use crate::etc::line::RationalPoint;
+use crate::etc::linalg::solve_integer_system;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
-type Pos2D = Coords2D<f64>;
-const COLLISION_RANGE: RangeInclusive<f64> = 200_000_000_000_000.0..=400_000_000_000_000.0;
-
-#[derive(Copy, Clone, PartialEq, Debug)]
-struct Pos3D {
-    x: f64, y: f64, z: f64
-}
+type Pos3D = Coords3D<i64>;
+const COLLISION_RANGE: RangeInclusive<i128> = 200_000_000_000_000..=400_000_000_000_000;
 
 #[derive(Copy, Clone, PartialEq, Debug)]
 struct HailData {
@@ -23,162 +22,76 @@ struct HailData {
     velocity: Pos3D,
 }
 
-#[derive(Copy, Clone, PartialEq, Debug)]
-struct HailData2D {
-    start: Pos2D,
-    velocity: Pos2D,
-}
-
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day24.txt").unwrap();
     let hails = input.lines().map(parse_hail).collect_vec();
 
     let sol1 = hails.iter()
-        .map(|h| project_hail(h, 2))
+        .map(|h| project_hail(h, Axis::Z))
         .tuple_combinations()
-        .filter(|pair| find_intersection(pair, false).is_some())
+        .filter(|(h1, h2)| match h1.intersection(h2) {
+            Intersection::Point(p) => in_collision_range(&p),
+            _ => false,
+        })
         .count();
 
     let rock = find_rock_coords(&hails);
-    let sol2 = rock.x as i64 + rock.y as i64 + rock.z as i64;
+    let sol2 = rock.x + rock.y + rock.z;
 
     (Solution::from(sol1), Solution::from(sol2))
 }
 
 ///////////////////////////////////////////////////////////////////////////////
 
+// The rock hits every hail i at some time t_i, so p + t_i*v = p_i + t_i*v_i,
+// which means that (p - p_i) and (v - v_i) are parallel and their cross
+// product is zero. Expanding it gives p×v - p×v_i - p_i×v + p_i×v_i = 0,
+// and the only non-linear term p×v is the same for every hail, so it
+// disappears when subtracting the equations of two hails. Doing so with
+// two pairs of hails gives 6 linear equations for the 6 unknowns.
 fn find_rock_coords(hails: &[HailData]) -> Pos3D {
-    let proj0 = hails.iter().map(|h| project_hail(h, 0)).collect_vec();
-    let proj1 = hails.iter().map(|h| project_hail(h, 1)).collect_vec();
-    let res0 = find_common_intersection(&proj0);
-    let res1 = find_common_intersection(&proj1);
-
-    Pos3D { x: res1.x.round(), y: res0.x.round(), z: res0.y.round() }
-}
-
-// Finds the speed of the rock in a 2D projection by brute-forcing
-// all possible values between -400 and 400. This works thanks to
-// the fact that the rock moving at a speed of v is equivalent to
-// it staying stationary and all the hails moving at their respective
-// speed minus v. In that case, if the speed of the rock is correct,
-// the paths of all pairs of hailstones will pass through the position
-// of the stationary rock, and thus all pairs of paths must cross
-// each other exactly at the starting position of the rock.
-fn find_common_intersection(projected: &[HailData2D]) -> Pos2D {
-    (-500..=500).into_par_iter().find_map_any(|speed_x| {
-        'outer: for speed_y in -500..=500 {
-            let stone_speed = Pos2D::new(speed_x as f64, speed_y as f64);
-            let relatives = projected.iter()
-                .map(|h| HailData2D { start: h.start, velocity: h.velocity - stone_speed } );
-
-            // Remove parallel paths and vertical ones because
-            // they don't work well with our continuous interception finder
-            let mut pairs = relatives
-                .filter(|h| h.velocity.x != 0.0)
-                .tuple_combinations()
-                .filter(|(h1, h2)| slope(h1) != slope(h2));
-
-            // If this is the correct stone speed, all intersections
-            // will be valid and at the same position
-            let first_intersect = find_intersection(&pairs.next().unwrap(), true);
-            if first_intersect.is_none() {
-                continue
-            }
-
-            let mut matched = 0;
-            for pair in pairs {
-                let other_intersect = find_intersection(&pair, true);
-                if !check_intersects(&other_intersect, &first_intersect) {
-                    continue 'outer;
-                } else {
-                    matched += 1;
-                    if matched >= 5 {
-                        return Some(first_intersect.unwrap());
-                    }
-                }
-            }
+    let wide = |c: Pos3D| Coords3D::new(c.x as i128, c.y as i128, c.z as i128);
+
+    // Some hails might give redundant equations, so keep trying until they don't
+    let solution = hails.iter().tuple_windows().find_map(|(h0, h1, h2)| {
+        let (p0, v0) = (wide(h0.start), wide(h0.velocity));
+        let mut matrix = vec![];
+        let mut rhs = vec![];
+
+        for h in [h1, h2] {
+            let (pi, vi) = (wide(h.start), wide(h.velocity));
+            let (a, b) = (vi - v0, pi - p0);
+            let c = pi.cross(&vi) - p0.cross(&v0);
+
+            // Coefficients of p×a + b×v = c, for [px, py, pz, vx, vy, vz]
+            matrix.push(vec![0, a.z, -a.y, 0, -b.z, b.y]);
+            matrix.push(vec![-a.z, 0, a.x, b.z, 0, -b.x]);
+            matrix.push(vec![a.y, -a.x, 0, -b.y, b.x, 0]);
+            rhs.extend([c.x, c.y, c.z]);
         }
 
-        None
-    }).unwrap()
-}
-
-fn check_intersects(e1: &Option<Pos2D>, e2: &Option<Pos2D>) -> bool {
-    if e1.is_none() || e2.is_none() {
-        return false;
-    }
-
-    let p1 = e1.unwrap();
-    let p2 = e2.unwrap();
-    (p1.x - p2.x).abs() < 1.0 && (p1.y - p2.y).abs() < 1.0
-}
-
-// Determines whether two hail trajectories in normal form will cross in the
-// designated area AND in the future for both hailstones
-fn find_intersection((h1, h2): &(HailData2D, HailData2D), part2: bool) -> Option<Pos2D> {
-    // Calculate the intersection point using the normal forms
-    let (a1, b1, c1) = normal_form(h1);
-    let (a2, b2, c2) = normal_form(h2);
-
-    if a1 == a2 {
-        return None;  // Paths are parallel, will never cross
-    }
-
-    let x = (b1*c2 - b2*c1) / (a1*b2 - a2*b1);
-    let y = (c1*a2 - c2*a1) / (a1*b2 - a2*b1);
-
-    let in_bounds = part2 || COLLISION_RANGE.contains(&x) && COLLISION_RANGE.contains(&y);
+        solve_integer_system(&matrix, &rhs).ok()
+    }).unwrap();
 
-    if time_at(h1, x) > 0.0 && time_at(h2, x) > 0.0 && in_bounds {
-        Some(Pos2D::new(x, y))
-    } else { None }
+    let coord = |val: &BigRational| {
+        assert!(val.is_integer(), "The rock must start at integer coordinates, found {val}");
+        val.to_integer().to_i64().expect("The rock coordinates must fit in an i64")
+    };
+    Pos3D::new(coord(&solution[0]), coord(&solution[1]), coord(&solution[2]))
 }
 
-// Calculates the time at which a given hail will be at a given x coordinate
-fn time_at(hail: &HailData2D, x: f64) -> f64 {
-    (x - hail.start.x) / hail.velocity.x
+fn in_collision_range(point: &RationalPoint) -> bool {
+    let (min, max) = (Ratio::from_integer(*COLLISION_RANGE.start()), Ratio::from_integer(*COLLISION_RANGE.end()));
+    (min..=max).contains(&point.x) && (min..=max).contains(&point.y)
 }
 
-// Computes the normal form of the line defined by the x and y
-// coordinates of the hail's initial position of speed,
-// in the form Ax + By + C = 0, returning (A, B, C)
-fn normal_form(hail: &HailData2D) -> (f64, f64, f64) {
-    let slope = slope(hail);
-    let a = slope;
-    let b = -1.0;
-    let c = slope * (-hail.start.x) + hail.start.y;
-    (a, b, c)
-}
-
-fn slope(hail: &HailData2D) -> f64 {
-    hail.velocity.y / hail.velocity.x
-}
-
-fn project_hail(hail: &HailData, axis: u8) -> HailData2D {
-    HailData2D {
-        start: project(hail.start, axis),
-        velocity: project(hail.velocity, axis),
-    }
-}
-
-fn project(coords: Pos3D, axis: u8) -> Pos2D {
-    match axis {
-        0 => Pos2D { x: coords.y, y: coords.z },
-        1 => Pos2D { x: coords.x, y: coords.z },
-        _ => Pos2D { x: coords.x, y: coords.y }
-    }
+fn project_hail(hail: &HailData, axis: Axis) -> Ray {
+    Ray::new(hail.start.project(axis), hail.velocity.project(axis))
 }
 
 fn parse_hail(line: &str) -> HailData {
     let (start, velocity) = line.split('@')
-        .map(parse_coords)
+        .map(|coords| coords.parse().unwrap())
         .collect_tuple().unwrap();
     HailData { start, velocity }
 }
-
-fn parse_coords(s: &str) -> Pos3D {
-    let (x, y, z) = s.split(',')
-        .map(|i| i.trim().parse().unwrap())
-        .collect_tuple().unwrap();
-    Pos3D { x, y, z }
-}
@@ -2,23 +2,26 @@ use std::fs::read_to_string;
 use std::hash::BuildHasherDefault;
 use itertools::Itertools;
 
-use petgraph::visit::{Dfs, Walker};
 use petgraph::prelude::{UnGraph, EdgeRef, NodeIndex, Outgoing};
 use priority_queue::PriorityQueue;
-use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use rustc_hash::{FxHashSet, FxHasher};
 
+use crate::etc::StrInterner;
+use crate::etc::graph::reachable_from;
 use crate::{Solution, SolutionPair};
 
 ///////////////////////////////////////////////////////////////////////////////
 
-type Graph<'a> = UnGraph<&'a str, u32>;
-type IndexMap<'a> = FxHashMap<&'a str, NodeIndex>;
+type Graph = UnGraph<usize, u32>;
 type NodeQueue = PriorityQueue<NodeIndex, u32, BuildHasherDefault<FxHasher>>;
 
 pub fn solve() -> SolutionPair {
     let input = read_to_string("input/day25.txt").unwrap();
-    let edges = parse_pairs(&input);
-    let (graph, _) = build_graph(edges);
+    let mut names = StrInterner::default();
+    let edges = parse_pairs(&input)
+        .map(|(n1, n2)| (names.get_id(n1), names.get_id(n2)))
+        .collect_vec();
+    let graph = build_graph(names.len(), &edges);
 
     let sol1 = solve_min_cut(graph);
     let sol2 = "Merry Christmas!";
@@ -43,7 +46,7 @@ fn solve_min_cut(mut g: Graph) -> usize {
         ));
 
         if cut == 3 {
-            let comp = uncontract(&contractions);
+            let comp = uncontract(nodes, &contractions);
             break comp * (nodes - comp);
         };
 
@@ -88,12 +91,12 @@ fn minimum_cut_phase(g: &Graph, start: NodeIndex) -> (u32, NodeIndex, NodeIndex)
 // contractions of two or more nodes. This function re-builds the graph
 // from the performed contractions and calculates the number of nodes
 // in the resulting connected component.
-fn uncontract(contrs: &[(&str, &str)]) -> usize {
+fn uncontract(nodes: usize, contrs: &[(usize, usize)]) -> usize {
     let components = &contrs[..contrs.len()-1];
-    let start = &contrs.last().unwrap().0;
+    let start = contrs.last().unwrap().0;
 
-    let (g, ids) = build_graph(components.iter().copied());
-    Dfs::new(&g, ids[start]).iter(&g).count()
+    let g = build_graph(nodes, components);
+    reachable_from(&g, NodeIndex::new(start)).len()
 }
 
 // Merges the node s(ource) into t(arget). Edges between s and t are dropped.
@@ -124,19 +127,19 @@ fn upsert_edge(g: &mut Graph, a: NodeIndex, b: NodeIndex, mut w: u32) {
     g.update_edge(a, b, w);
 }
 
-// Builds an undirected graph from a set of edges
-fn build_graph<'a, E>(edges: E) -> (Graph<'a>, IndexMap<'a>)
-where E: Iterator<Item = (&'a str, &'a str)> {
-    let mut ids = FxHashMap::default();
-    let mut graph = Graph::new_undirected();
+// Builds an undirected graph from a set of edges between node ids.
+// Every node is stored at the index that matches its id.
+fn build_graph(nodes: usize, edges: &[(usize, usize)]) -> Graph {
+    let mut graph = Graph::with_capacity(nodes, edges.len());
+    for id in 0..nodes {
+        graph.add_node(id);
+    }
 
-    for (n1, n2) in edges {
-        let n1_ix = *ids.entry(n1).or_insert_with(|| graph.add_node(n1));
-        let n2_ix = *ids.entry(n2).or_insert_with(|| graph.add_node(n2));
-        graph.add_edge(n1_ix, n2_ix, 1);
+    for &(n1, n2) in edges {
+        graph.add_edge(NodeIndex::new(n1), NodeIndex::new(n2), 1);
     }
 
-    (graph, ids)
+    graph
 }
 
 // Input parser
@@ -0,0 +1,263 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+use super::image::GridImage;
+use super::render::Color;
+use super::vecmat::VecMat;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/** Environment variable with the directory where solvers save their frames */
+pub const FRAMES_DIR_VAR: &str = "AOC_FRAMES_DIR";
+
+/** A single snapshot of a simulation */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+    Text(String),
+    Image(GridImage),
+}
+
+/** Collects snapshots of a step-wise simulation so that they can be
+    replayed or exported afterwards. A disabled recorder ignores all
+    snapshots, so solvers can keep the recording hooks at no cost. */
+#[derive(Clone, Debug)]
+pub struct FrameRecorder {
+    frames: Vec<Frame>,
+    enabled: bool,
+    sample_every: usize,
+    pushed: usize,
+    output_dir: Option<PathBuf>,
+}
+
+impl FrameRecorder {
+    pub fn new() -> Self {
+        Self { frames: vec![], enabled: true, sample_every: 1, pushed: 0, output_dir: None }
+    }
+
+    pub fn disabled() -> Self {
+        Self { enabled: false, ..Self::new() }
+    }
+
+    /** A recorder that is only enabled when the `AOC_FRAMES_DIR` environment
+        variable is set, so that solvers can always keep it around. Its frames
+        are saved to a sub-directory of that one by `save_requested`. */
+    pub fn from_env(name: &str) -> Self {
+        match std::env::var_os(FRAMES_DIR_VAR) {
+            Some(dir) => Self { output_dir: Some(Path::new(&dir).join(name)), ..Self::new() },
+            None => Self::disabled(),
+        }
+    }
+
+    /** Only keeps one out of every `n` snapshots pushed */
+    pub fn sample_every(mut self, n: usize) -> Self {
+        assert!(n > 0, "The sampling interval must be positive");
+        self.sample_every = n;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /** Records the textual representation of anything that can be displayed */
+    pub fn push_text<D: Display + ?Sized>(&mut self, snapshot: &D) {
+        if self.should_record() {
+            self.frames.push(Frame::Text(snapshot.to_string()));
+        }
+    }
+
+    pub fn push_image(&mut self, image: GridImage) {
+        if self.should_record() {
+            self.frames.push(Frame::Image(image));
+        }
+    }
+
+    /** Records a grid as an image, see `VecMat::to_image` */
+    pub fn push_grid<T: Copy, F: Fn(T) -> Color>(&mut self, grid: &VecMat<T>, scale: usize, color: F) {
+        if self.should_record() {
+            self.frames.push(Frame::Image(grid.to_image(scale, color)));
+        }
+    }
+
+    /** Saves every frame as a numbered file in a directory, which is
+        created if needed. Text frames are saved as .txt files and
+        image frames as .ppm files. */
+    pub fn save_frames<P: AsRef<Path>>(&self, dir: P) -> io::Result<()> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for (i, frame) in self.frames.iter().enumerate() {
+            match frame {
+                Frame::Text(text) => fs::write(dir.join(format!("frame_{i:05}.txt")), text)?,
+                Frame::Image(image) => image.save_ppm(dir.join(format!("frame_{i:05}.ppm")))?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /** Saves the frames to the directory chosen through the environment,
+        see `from_env`. Does nothing for other recorders. */
+    pub fn save_requested(&self) -> io::Result<()> {
+        match &self.output_dir {
+            Some(dir) => self.save_frames(dir),
+            None => Ok(()),
+        }
+    }
+
+    /** Plays the text frames back in the terminal, waiting
+        the given amount of time between each of them */
+    pub fn replay(&self, delay: Duration) -> io::Result<()> {
+        let mut out = io::stdout().lock();
+
+        for frame in &self.frames {
+            if let Frame::Text(text) = frame {
+                write!(out, "{CLEAR_SCREEN}{text}")?;
+                out.flush()?;
+                sleep(delay);
+            }
+        }
+
+        Ok(())
+    }
+
+    /** Saves the image frames as a looping animated GIF, with the given
+        delay between frames. All images must have the same dimensions,
+        which can't be larger than 65535 pixels. */
+    #[cfg(feature = "gif")]
+    pub fn save_gif<P: AsRef<Path>>(&self, path: P, delay: Duration) -> io::Result<()> {
+        let images = self.frames.iter()
+            .filter_map(|frame| match frame {
+                Frame::Image(image) => Some(image),
+                Frame::Text(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        let Some(&first) = images.first() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "There are no image frames to save"));
+        };
+
+        // Everything is checked before creating the file, so that no
+        // partial GIF is left behind when the frames can't be saved
+        if images.iter().any(|image| (image.width(), image.height()) != (first.width(), first.height())) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "All frames must have the same dimensions"));
+        }
+
+        // GIF dimensions are stored as 16-bit values
+        let (Ok(width), Ok(height)) = (u16::try_from(first.width()), u16::try_from(first.height())) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "GIF frames can't be larger than 65535 pixels in any dimension"));
+        };
+
+        // GIF delays are measured in hundredths of a second, and
+        // longer ones are clamped to the largest one it can store
+        let delay = u16::try_from(delay.as_millis() / 10).unwrap_or(u16::MAX);
+
+        let out = io::BufWriter::new(fs::File::create(path)?);
+        let mut encoder = gif::Encoder::new(out, width, height, &[]).map_err(io::Error::other)?;
+        encoder.set_repeat(gif::Repeat::Infinite).map_err(io::Error::other)?;
+
+        for image in images {
+            let mut frame = gif::Frame::from_rgb_speed(width, height, image.pixels(), 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame).map_err(io::Error::other)?;
+        }
+
+        Ok(())
+    }
+
+    fn should_record(&mut self) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        self.pushed += 1;
+        (self.pushed - 1) % self.sample_every == 0
+    }
+}
+
+impl Default for FrameRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sampled_frame_count() {
+        let mut recorder = FrameRecorder::new().sample_every(3);
+        for i in 0..10 {
+            recorder.push_text(&i);
+        }
+
+        // Frames 0, 3, 6 and 9 are kept
+        assert_eq!(recorder.len(), 4);
+        assert_eq!(recorder.frames()[1], Frame::Text("3".to_owned()));
+    }
+
+    #[test]
+    fn disabled_recorder() {
+        let mut recorder = FrameRecorder::disabled();
+        recorder.push_text("ignored");
+        recorder.push_grid(&VecMat::new(2, 2, 0), 1, |_| Color::Black);
+
+        assert!(recorder.is_empty());
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif_too_large() {
+        let mut recorder = FrameRecorder::new();
+        recorder.push_grid(&VecMat::new(70_000, 1, 0), 1, |_| Color::Black);
+
+        let path = std::env::temp_dir().join("aoc_2023_too_large.gif");
+        let err = recorder.save_gif(&path, Duration::from_millis(100)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif_with_mismatched_frames() {
+        let mut recorder = FrameRecorder::new();
+        recorder.push_grid(&VecMat::new(2, 2, 0), 1, |_| Color::Black);
+        recorder.push_grid(&VecMat::new(3, 2, 0), 1, |_| Color::Black);
+
+        let path = std::env::temp_dir().join("aoc_2023_mismatched.gif");
+        let _ = fs::remove_file(&path);
+        let err = recorder.save_gif(&path, Duration::from_millis(100)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(!path.exists());
+    }
+
+    #[cfg(feature = "gif")]
+    #[test]
+    fn gif_with_long_delay() {
+        let mut recorder = FrameRecorder::new();
+        recorder.push_grid(&VecMat::new(2, 2, 0), 1, |_| Color::Black);
+
+        let path = std::env::temp_dir().join("aoc_2023_long_delay.gif");
+        recorder.save_gif(&path, Duration::from_secs(1_000)).unwrap();
+        assert!(path.exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
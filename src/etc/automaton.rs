@@ -0,0 +1,286 @@
+#![allow(dead_code)]
+
+use std::hash::Hash;
+use std::rc::Rc;
+
+use rayon::prelude::*;
+
+use super::bitgrid::BitGrid;
+use super::coords::Coords2D;
+use super::cycle::{find_cycle_within_by_key, Cycle};
+use super::tiled_grid::TiledGrid;
+use super::vecmat::VecMat;
+
+type Pos = Coords2D<i64>;
+
+/** Runs a grid through successive generations using a rule, keeping track
+    of the population of every generation as measured by a counting function.
+    The grid can be any type, such as a VecMat, a BitGrid or a TiledGrid. */
+#[derive(Clone, Debug)]
+pub struct Automaton<G, P: Fn(&G) -> usize> {
+    grid: G,
+    generation: usize,
+    populations: Vec<usize>,
+    population: P,
+    cycle: Option<Cycle>,
+}
+
+impl<G, P: Fn(&G) -> usize> Automaton<G, P> {
+    pub fn new(grid: G, population: P) -> Self {
+        let populations = vec![population(&grid)];
+        Self { grid, generation: 0, populations, population, cycle: None }
+    }
+
+    pub fn grid(&self) -> &G {
+        &self.grid
+    }
+
+    pub fn into_grid(self) -> G {
+        self.grid
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /** The population of every generation that was simulated until a
+        cycle was found, starting with the initial one */
+    pub fn populations(&self) -> &[usize] {
+        &self.populations
+    }
+
+    /** The population at any generation up to the current one. Generations
+        that were skipped thanks to a cycle are also known, as well as those
+        simulated after finding it, assuming that they used the same rule. */
+    pub fn population_at(&self, generation: usize) -> Option<usize> {
+        if generation > self.generation {
+            return None;
+        }
+
+        let simulated = self.cycle.map_or(generation, |cycle| cycle.equivalent_step(generation));
+        self.populations.get(simulated).copied()
+    }
+
+    /** The cycle found by `advance_to`, if any */
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /** Advances one generation with a rule that updates the grid in place,
+        so cells may see the already updated values of other ones */
+    pub fn step_in_place<F: FnOnce(&mut G)>(&mut self, rule: F) {
+        rule(&mut self.grid);
+        self.record();
+    }
+
+    /** Advances one generation with a rule that computes the new grid from
+        the old one, so every cell is updated at the same time */
+    pub fn step<F: FnOnce(&G) -> G>(&mut self, rule: F) {
+        self.grid = rule(&self.grid);
+        self.record();
+    }
+
+    pub fn run_in_place<F: FnMut(&mut G)>(&mut self, generations: usize, mut rule: F) {
+        for _ in 0..generations {
+            self.step_in_place(&mut rule);
+        }
+    }
+
+    pub fn run<F: FnMut(&G) -> G>(&mut self, generations: usize, mut rule: F) {
+        for _ in 0..generations {
+            self.step(&mut rule);
+        }
+    }
+
+    // The populations are only appended until a cycle is found, since the
+    // later ones are already known as long as the rule stays the same
+    fn record(&mut self) {
+        self.generation += 1;
+        if self.cycle.is_none() {
+            self.populations.push((self.population)(&self.grid));
+        }
+    }
+}
+
+impl<G: Clone + Hash + Eq, P: Fn(&G) -> usize> Automaton<G, P> {
+    /** Advances to the given generation using an in-place rule. As soon as
+        a grid repeats a previous one, the cycle is remembered and the rest
+        of the generations are skipped. Only the grids simulated by this call
+        are searched for repetitions, starting from the current one. Once a
+        cycle is known, every later generation must use the same rule. */
+    pub fn advance_to<F: FnMut(&mut G)>(&mut self, target: usize, mut rule: F) {
+        if target <= self.generation {
+            return;
+        }
+
+        // Once the cycle is known, only the offset within it has to be simulated
+        if let Some(cycle) = self.cycle {
+            let pending = cycle.equivalent_step(target) + cycle.period - cycle.equivalent_step(self.generation);
+            for _ in 0..pending % cycle.period {
+                rule(&mut self.grid);
+            }
+            self.generation = target;
+            return;
+        }
+
+        // Every grid is stored once and shared between the history and the
+        // cycle detection, which compares the grids themselves on a repetition
+        let first = self.generation;
+        let start = Rc::new(self.grid.clone());
+        let mut history = vec![Rc::clone(&start)];
+        let found = find_cycle_within_by_key(start, |grid| {
+            let mut next = G::clone(grid);
+            rule(&mut next);
+            self.populations.push((self.population)(&next));
+
+            let next = Rc::new(next);
+            history.push(Rc::clone(&next));
+            next
+        }, Rc::clone, target - first);
+
+        let last = match found {
+            Some(cycle) => {
+                self.cycle = Some(Cycle { offset: first + cycle.offset, period: cycle.period });
+                history.swap_remove(cycle.equivalent_step(target - first))
+            },
+            None => history.pop().unwrap(),
+        };
+
+        self.grid = Rc::unwrap_or_clone(last);
+        self.generation = target;
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+// Synchronous rules that compute every cell from its value and the previous
+// state of the grid, which the rule can use to look at the neighbors
+
+impl<T: Copy> VecMat<T> {
+    /** Computes a new matrix where every cell is the result of a rule */
+    pub fn map_cells<F>(&self, rule: F) -> VecMat<T>
+    where F: Fn(&VecMat<T>, Pos, T) -> T
+    {
+        let data = self.indexed_iter().map(|(pos, val)| rule(self, pos, val)).collect();
+        VecMat::from_data(self.width(), self.height(), data)
+    }
+
+    /** Same as `map_cells`, but computing the cells in parallel */
+    pub fn par_map_cells<F>(&self, rule: F) -> VecMat<T>
+    where T: Send + Sync,
+          F: Fn(&VecMat<T>, Pos, T) -> T + Sync
+    {
+        let data = (0..self.width() * self.height()).into_par_iter()
+            .map(|i| {
+                let pos = self.coords(i);
+                rule(self, pos, self[pos])
+            })
+            .collect();
+        VecMat::from_data(self.width(), self.height(), data)
+    }
+}
+
+impl<T: Copy> TiledGrid<T> {
+    /** Computes a new grid where every cell of the base tile is the result
+        of a rule, which sees the neighbors past the edges of the tile */
+    pub fn map_cells<F>(&self, rule: F) -> TiledGrid<T>
+    where F: Fn(&TiledGrid<T>, Pos, T) -> T
+    {
+        let tile = self.tile().map_cells(|_, pos, val| rule(self, pos, val));
+        TiledGrid::new(tile, self.mode())
+    }
+}
+
+impl BitGrid {
+    /** The cells that are orthogonally adjacent to a set one */
+    pub fn adjacent4(&self) -> BitGrid {
+        let mut res = self.shifted(0, -1);
+        res |= &self.shifted(0, 1);
+        res |= &self.shifted(-1, 0);
+        res |= &self.shifted(1, 0);
+        res
+    }
+
+    /** The amount of set cells among the 8 neighbors of every cell, as
+        one grid per count from 0 to 8, where a cell is set in the grid
+        that corresponds to its count */
+    pub fn neighbor_counts(&self) -> [BitGrid; 9] {
+        let empty = BitGrid::new(self.width(), self.height());
+        let mut counts: [BitGrid; 9] = std::array::from_fn(|_| empty.clone());
+        counts[0] = !&empty;
+
+        let offsets = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+        for (dx, dy) in offsets {
+            let neighbor = self.shifted(dx, dy);
+
+            // Cells with a set neighbor move up one count, from the top down
+            for count in (1..9).rev() {
+                let moved = &counts[count - 1] & &neighbor;
+                counts[count] |= &moved;
+                counts[count - 1] ^= &moved;
+            }
+        }
+
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> ... -> 7 -> 3 -> ...
+    fn rule(x: &mut u32) {
+        *x = if *x == 7 { 3 } else { *x + 1 };
+    }
+
+    fn simulate(generations: usize) -> u32 {
+        let mut x = 0;
+        (0..generations).for_each(|_| rule(&mut x));
+        x
+    }
+
+    #[test]
+    fn advance_to_skips_the_cycle() {
+        let mut automaton = Automaton::new(0, |&x| x as usize);
+        automaton.advance_to(1000, rule);
+
+        assert_eq!(*automaton.grid(), simulate(1000));
+        assert_eq!(automaton.cycle(), Some(Cycle { offset: 3, period: 5 }));
+        for generation in 0..=1000 {
+            assert_eq!(automaton.population_at(generation), Some(simulate(generation) as usize));
+        }
+
+        automaton.advance_to(2024, rule);
+        assert_eq!(*automaton.grid(), simulate(2024));
+    }
+
+    #[test]
+    fn advance_to_compares_whole_grids() {
+        // Every grid has the same hash, so only equality tells them apart
+        #[derive(Clone, PartialEq, Eq)]
+        struct Colliding(u32);
+
+        impl Hash for Colliding {
+            fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+        }
+
+        let mut automaton = Automaton::new(Colliding(0), |c| c.0 as usize);
+        automaton.advance_to(1000, |c| rule(&mut c.0));
+        assert_eq!(automaton.grid().0, simulate(1000));
+        assert_eq!(automaton.cycle(), Some(Cycle { offset: 3, period: 5 }));
+    }
+
+    #[test]
+    fn advance_to_resumes_from_any_generation() {
+        let mut automaton = Automaton::new(0, |&x| x as usize);
+        automaton.advance_to(2, rule);
+        assert_eq!(automaton.cycle(), None);
+
+        automaton.run_in_place(2, rule);
+        automaton.advance_to(1001, rule);
+        assert_eq!(*automaton.grid(), simulate(1001));
+        assert_eq!(automaton.cycle(), Some(Cycle { offset: 4, period: 5 }));
+        assert_eq!(automaton.population_at(1001), Some(simulate(1001) as usize));
+    }
+}
@@ -0,0 +1,369 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use num_traits::int::PrimInt;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/** A 2D grid of booleans packed into bits, with each row stored as a
+    bitset of u64 words. Whole-grid operations work on entire words at a
+    time. Bits past the width in the last word of every row are always 0,
+    so that equality and hashing only depend on the actual cells. */
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    data: Vec<u64>,
+}
+
+impl BitGrid {
+    /** Creates a grid with all cells unset */
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD_BITS);
+        Self { width, height, words_per_row, data: vec![0; words_per_row * height] }
+    }
+
+    /** Creates a grid with the cells of a matrix that satisfy a predicate set */
+    pub fn from_vecmat<T: Copy, F: Fn(T) -> bool>(mat: &VecMat<T>, pred: F) -> Self {
+        let mut grid = Self::new(mat.width(), mat.height());
+        for (pos, val) in mat.indexed_iter::<usize>() {
+            if pred(val) {
+                grid.set(pos, true);
+            }
+        }
+        grid
+    }
+
+    /** Converts the grid into a matrix, using one value for the
+        set cells and another for the unset ones */
+    pub fn to_vecmat<T: Copy>(&self, on: T, off: T) -> VecMat<T> {
+        let data = (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .map(|(x, y)| if self.get_bit(x, y) { on } else { off })
+            .collect();
+        VecMat::from_data(self.width, self.height, data)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_in_bounds<I: PrimInt>(&self, pos: Coords2D<I>) -> bool {
+        let x = pos.x.to_i64().unwrap();
+        let y = pos.y.to_i64().unwrap();
+        x >= 0 && y >= 0 && x < self.width as i64 && y < self.height as i64
+    }
+
+    pub fn get<I: PrimInt + Display>(&self, pos: Coords2D<I>) -> bool {
+        let (x, y) = self.checked_coords(pos);
+        self.get_bit(x, y)
+    }
+
+    pub fn get_or<I: PrimInt + Display>(&self, pos: Coords2D<I>, default: bool) -> bool {
+        if self.is_in_bounds(pos) {
+            self.get(pos)
+        } else {
+            default
+        }
+    }
+
+    pub fn set<I: PrimInt + Display>(&mut self, pos: Coords2D<I>, val: bool) {
+        let (x, y) = self.checked_coords(pos);
+        let word = &mut self.data[y * self.words_per_row + x / WORD_BITS];
+        let mask = 1 << (x % WORD_BITS);
+
+        if val {
+            *word |= mask;
+        } else {
+            *word &= !mask;
+        }
+    }
+
+    /** Sets or unsets all cells at once */
+    pub fn fill(&mut self, val: bool) {
+        let word = if val { u64::MAX } else { 0 };
+        self.data.iter_mut().for_each(|w| *w = word);
+        self.clear_padding();
+    }
+
+    /** The words that store a row, with the cell at x = 0
+        in the least significant bit of the first word */
+    pub fn row(&self, y: usize) -> &[u64] {
+        assert!(y < self.height, "y index out of bounds: {y} but height is {}", self.height);
+        &self.data[y * self.words_per_row .. (y + 1) * self.words_per_row]
+    }
+
+    /** Amount of set cells in the whole grid */
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /** Amount of set cells in a row */
+    pub fn row_count_ones(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /** Iterates over the positions of all the set cells, in row-major order */
+    pub fn iter_ones<I: PrimInt>(&self) -> impl Iterator<Item = Coords2D<I>> + '_ {
+        self.data.iter().enumerate().flat_map(move |(i, &word)| {
+            let y = i / self.words_per_row;
+            let base_x = (i % self.words_per_row) * WORD_BITS;
+            BitIter(word).map(move |bit| Coords2D::new(I::from(base_x + bit).unwrap(), I::from(y).unwrap()))
+        })
+    }
+
+    /** Returns a copy of the grid with all cells moved `dx` columns
+        to the right and `dy` rows down. Negative values move them left
+        and up. Cells that are moved out of the grid are lost, and the
+        vacated ones are unset. */
+    pub fn shifted(&self, dx: i64, dy: i64) -> Self {
+        let mut res = Self::new(self.width, self.height);
+
+        for y in 0..self.height {
+            let src_y = y as i64 - dy;
+            if src_y < 0 || src_y >= self.height as i64 {
+                continue;
+            }
+
+            let src = self.row(src_y as usize);
+            let start = y * self.words_per_row;
+            let dst = &mut res.data[start .. start + self.words_per_row];
+
+            if dx >= 0 {
+                shift_row_up(src, dst, dx as usize);
+            } else {
+                shift_row_down(src, dst, dx.unsigned_abs() as usize);
+            }
+        }
+
+        res.clear_padding();
+        res
+    }
+
+    fn get_bit(&self, x: usize, y: usize) -> bool {
+        self.data[y * self.words_per_row + x / WORD_BITS] & (1 << (x % WORD_BITS)) != 0
+    }
+
+    fn checked_coords<I: PrimInt + Display>(&self, pos: Coords2D<I>) -> (usize, usize) {
+        let x = pos.x.to_usize().unwrap_or_else(|| panic!("X index not valid: {}", pos.x));
+        let y = pos.y.to_usize().unwrap_or_else(|| panic!("Y index not valid: {}", pos.y));
+        assert!(x < self.width, "x index out of bounds: {x} but width is {}", self.width);
+        assert!(y < self.height, "y index out of bounds: {y} but height is {}", self.height);
+        (x, y)
+    }
+
+    // Unsets the unused bits at the end of every row
+    fn clear_padding(&mut self) {
+        let used = self.width % WORD_BITS;
+        if used == 0 {
+            return;
+        }
+
+        let mask = (1 << used) - 1;
+        for y in 0..self.height {
+            self.data[(y + 1) * self.words_per_row - 1] &= mask;
+        }
+    }
+
+    fn zip_words<F: Fn(u64, u64) -> u64>(&mut self, other: &Self, op: F) {
+        assert_eq!((self.width, self.height), (other.width, other.height), "Grid dimensions must match");
+        for (a, b) in self.data.iter_mut().zip(&other.data) {
+            *a = op(*a, *b);
+        }
+    }
+}
+
+// Moves the bits of a row towards the more significant positions
+fn shift_row_up(src: &[u64], dst: &mut [u64], n: usize) {
+    let (words, bits) = (n / WORD_BITS, n % WORD_BITS);
+
+    for i in words..dst.len() {
+        dst[i] = src[i - words] << bits;
+        if bits > 0 && i > words {
+            dst[i] |= src[i - words - 1] >> (WORD_BITS - bits);
+        }
+    }
+}
+
+// Moves the bits of a row towards the less significant positions
+fn shift_row_down(src: &[u64], dst: &mut [u64], n: usize) {
+    let (words, bits) = (n / WORD_BITS, n % WORD_BITS);
+
+    for i in 0..dst.len().saturating_sub(words) {
+        dst[i] = src[i + words] >> bits;
+        if bits > 0 && i + words + 1 < src.len() {
+            dst[i] |= src[i + words + 1] << (WORD_BITS - bits);
+        }
+    }
+}
+
+// Iterates over the indices of the set bits in a word
+struct BitIter(u64);
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let bit = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($trait_:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $op:tt) => {
+        impl $assign_trait<&BitGrid> for BitGrid {
+            fn $assign_method(&mut self, rhs: &BitGrid) {
+                self.zip_words(rhs, |a, b| a $op b);
+            }
+        }
+
+        impl $trait_<&BitGrid> for &BitGrid {
+            type Output = BitGrid;
+
+            fn $method(self, rhs: &BitGrid) -> Self::Output {
+                let mut res = self.clone();
+                res.$assign_method(rhs);
+                res
+            }
+        }
+    }
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> Self::Output {
+        let mut res = self.clone();
+        res.data.iter_mut().for_each(|w| *w = !*w);
+        res.clear_padding();
+        res
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.get_bit(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    const WIDTHS: [usize; 7] = [1, 2, 63, 64, 65, 128, 130];
+
+    fn pattern(width: usize, height: usize) -> BitGrid {
+        let mut grid = BitGrid::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                grid.set(Coords2D::new(x, y), (x * 7 + y * 3) % 5 < 2);
+            }
+        }
+        grid
+    }
+
+    // Shifts the cells one at a time
+    fn naive_shift(grid: &BitGrid, dx: i64, dy: i64) -> BitGrid {
+        let mut res = BitGrid::new(grid.width(), grid.height());
+        for pos in grid.iter_ones::<i64>() {
+            let target = Coords2D::new(pos.x + dx, pos.y + dy);
+            if res.is_in_bounds(target) {
+                res.set(target, true);
+            }
+        }
+        res
+    }
+
+    fn hash_of(grid: &BitGrid) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        grid.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn shifts_match_moving_every_cell() {
+        for width in WIDTHS {
+            let grid = pattern(width, 3);
+            for dx in [-130, -65, -64, -63, -1, 0, 1, 63, 64, 65, 130] {
+                for dy in [-1, 0, 2] {
+                    assert_eq!(grid.shifted(dx, dy), naive_shift(&grid, dx, dy), "width {width}, shift ({dx}, {dy})");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shifts_carry_across_word_boundaries() {
+        let mut grid = BitGrid::new(130, 1);
+        grid.set(Coords2D::new(63, 0), true);
+
+        assert!(grid.shifted(1, 0).get(Coords2D::new(64, 0)));
+        assert!(grid.shifted(66, 0).get(Coords2D::new(129, 0)));
+        assert_eq!(grid.shifted(67, 0).count_ones(), 0);
+        assert!(grid.shifted(-63, 0).get(Coords2D::new(0, 0)));
+
+        let moved = grid.shifted(2, 0);
+        assert!(moved.shifted(-2, 0).get(Coords2D::new(63, 0)));
+    }
+
+    #[test]
+    fn not_keeps_the_padding_clear() {
+        for width in WIDTHS {
+            let grid = pattern(width, 2);
+            let inverted = !&grid;
+            assert_eq!(inverted.count_ones(), width * 2 - grid.count_ones());
+            assert_eq!(!&inverted, grid);
+
+            let used = width % WORD_BITS;
+            if used > 0 {
+                assert_eq!(inverted.row(1).last().unwrap() >> used, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn equal_grids_hash_equally() {
+        for width in WIDTHS {
+            let set_one_by_one = pattern(width, 2);
+            let from_mat = BitGrid::from_vecmat(&set_one_by_one.to_vecmat(true, false), |b| b);
+            let double_not = !&!&set_one_by_one;
+            let shifted_back = &set_one_by_one.shifted(0, 1).shifted(0, -1) | &set_one_by_one;
+
+            for other in [from_mat, double_not, shifted_back] {
+                assert_eq!(other, set_one_by_one);
+                assert_eq!(hash_of(&other), hash_of(&set_one_by_one));
+            }
+
+            let mut filled = BitGrid::new(width, 2);
+            filled.fill(true);
+            assert_eq!(hash_of(&filled), hash_of(&!&BitGrid::new(width, 2)));
+        }
+    }
+}
@@ -1,5 +1,7 @@
-use std::ops::{Add, Sub, AddAssign, SubAssign, Mul, Neg};
+use std::ops::{Add, Sub, AddAssign, SubAssign, Mul, Neg, Index, IndexMut};
+use std::str::FromStr;
 use num_traits::{Float, Num, PrimInt, Signed};
+use Direction::*;
 
 /** A pair of numbers representing 2D coordinates. */
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -154,3 +156,468 @@ impl <T: Num + Neg<Output = T>> Neg for Coords2D<T> {
         Self::new(-self.x, -self.y)
     }
 }
+
+///////////////////////////////////////////////////////////////////////////////
+
+/** Three numbers representing 3D coordinates. */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coords3D<T: Num> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/** One of the three axes of 3D space */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Axis {
+    X, Y, Z
+}
+
+impl<T: Num> Coords3D<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn origin() -> Self {
+        (T::zero(), T::zero(), T::zero()).into()
+    }
+}
+
+impl<T: Num + Copy> Coords3D<T> {
+    pub fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /** Projects the coordinates onto the plane perpendicular to an axis,
+        keeping the other two in their original order */
+    pub fn project(&self, axis: Axis) -> Coords2D<T> {
+        match axis {
+            Axis::X => Coords2D::new(self.y, self.z),
+            Axis::Y => Coords2D::new(self.x, self.z),
+            Axis::Z => Coords2D::new(self.x, self.y),
+        }
+    }
+
+    pub fn get(&self, axis: Axis) -> T {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+}
+
+impl<T: Num + Signed + Copy> Coords3D<T> {
+    /** The 6 neighbors that share a face with this position */
+    pub fn neighbors(&self) -> [Self; 6] {
+        let (zero, one) = (T::zero(), T::one());
+        [*self + Self::new(one, zero, zero), *self - Self::new(one, zero, zero),
+         *self + Self::new(zero, one, zero), *self - Self::new(zero, one, zero),
+         *self + Self::new(zero, zero, one), *self - Self::new(zero, zero, one)]
+    }
+
+    /** The 26 neighbors that share a face, edge or corner with this position */
+    pub fn neighbors_diag(&self) -> [Self; 26] {
+        let deltas = [-T::one(), T::zero(), T::one()];
+        let mut res = [*self; 26];
+        let mut i = 0;
+
+        for dx in deltas {
+            for dy in deltas {
+                for dz in deltas {
+                    if !(dx.is_zero() && dy.is_zero() && dz.is_zero()) {
+                        res[i] = *self + Self::new(dx, dy, dz);
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        res
+    }
+}
+
+impl<T: PrimInt + Signed> Coords3D<T> {
+    pub fn manhattan_dist(&self, other: &Self) -> T {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+}
+
+impl<T: Float + Signed> Coords3D<T> {
+    pub fn euclidean_dist(&self, other: &Self) -> T {
+        let sqs = (self.x - other.x).powi(2) + (self.y - other.y).powi(2) + (self.z - other.z).powi(2);
+        sqs.sqrt()
+    }
+}
+
+/** Parses coordinates in the form `x,y,z`, ignoring any whitespace around the numbers */
+impl<T: Num + FromStr> FromStr for Coords3D<T> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s.split(',')
+            .map(|part| part.trim().parse::<T>().map_err(|_| format!("Not a valid coordinate: {part}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match <[T; 3]>::try_from(parts) {
+            Ok([x, y, z]) => Ok(Self::new(x, y, z)),
+            Err(_) => Err(format!("Expected 3 coordinates: {s}")),
+        }
+    }
+}
+
+impl<T: Num> From<(T, T, T)> for Coords3D<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl<T: Num> From<Coords3D<T>> for (T, T, T) {
+    fn from(c: Coords3D<T>) -> (T, T, T) {
+        (c.x, c.y, c.z)
+    }
+}
+
+impl <T: Num> Add<Coords3D<T>> for Coords3D<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl <T: Num + Copy> Add<Coords3D<T>> for &Coords3D<T> {
+    type Output = Coords3D<T>;
+
+    fn add(self, rhs: Coords3D<T>) -> Self::Output {
+        Coords3D::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl <T: Num + Copy> AddAssign<Coords3D<T>> for Coords3D<T> {
+    fn add_assign(&mut self, rhs: Coords3D<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl <T: Num> Sub<Coords3D<T>> for Coords3D<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl <T: Num + Copy> SubAssign<Coords3D<T>> for Coords3D<T> {
+    fn sub_assign(&mut self, rhs: Coords3D<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl <T: Num + Copy> Add<&Coords3D<T>> for Coords3D<T> {
+    type Output = Self;
+
+    fn add(self, rhs: &Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl <T: Num + Copy> Sub<&Coords3D<T>> for Coords3D<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl <T: Num + Copy> Mul<T> for Coords3D<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl <T: Num + Neg<Output = T>> Neg for Coords3D<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+/** One of the 8 directions of movement in a grid. Variants are ordered
+    clockwise starting from north, which corresponds to `Coords2D::up()`. */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    N, NE, E, SE, S, SW, W, NW
+}
+
+impl Direction {
+    pub const CARDINAL: [Direction; 4] = [N, E, S, W];
+    pub const ALL: [Direction; 8] = [N, NE, E, SE, S, SW, W, NW];
+
+    /** Rotates the direction clockwise in increments of 45 degrees.
+        Negative amounts rotate counter-clockwise. */
+    pub fn rotate(self, steps: i32) -> Self {
+        Self::ALL[(self as i32 + steps).rem_euclid(8) as usize]
+    }
+
+    /** Rotates the direction 90 degrees counter-clockwise */
+    pub fn turn_left(self) -> Self {
+        self.rotate(-2)
+    }
+
+    /** Rotates the direction 90 degrees clockwise */
+    pub fn turn_right(self) -> Self {
+        self.rotate(2)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_cardinal(self) -> bool {
+        self as u8 % 2 == 0
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        !self.is_cardinal()
+    }
+
+    /** Position of the direction in `Direction::ALL` */
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /** Position of the direction in `Direction::CARDINAL`.
+        Panics if the direction is a diagonal. */
+    pub fn cardinal_index(self) -> usize {
+        assert!(self.is_cardinal(), "Not a cardinal direction: {self:?}");
+        self as usize / 2
+    }
+
+    /** The unit vector for this direction, with y growing downwards */
+    pub fn to_coords<T: Num + Signed + Copy>(self) -> Coords2D<T> {
+        let (x, y) = match self {
+            N  => (0, -1),
+            NE => (1, -1),
+            E  => (1, 0),
+            SE => (1, 1),
+            S  => (0, 1),
+            SW => (-1, 1),
+            W  => (-1, 0),
+            NW => (-1, -1),
+        };
+        (Self::unit::<T>(x), Self::unit::<T>(y)).into()
+    }
+
+    /** The direction that corresponds to a unit vector (including
+        diagonal ones), or `None` if it isn't one */
+    pub fn from_coords<T: Num + Signed + Copy>(coords: Coords2D<T>) -> Option<Self> {
+        Self::ALL.into_iter().find(|dir| dir.to_coords() == coords)
+    }
+
+    /** Parses a direction from a single character, which can be one
+        of `UDLR`, `^v<>` or `NESW` */
+    pub fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            'U' | '^' | 'N' => Some(N),
+            'D' | 'v' | 'S' => Some(S),
+            'L' | '<' | 'W' => Some(W),
+            'R' | '>' | 'E' => Some(E),
+            _ => None,
+        }
+    }
+
+    fn unit<T: Num + Signed>(val: i8) -> T {
+        match val {
+            -1 => -T::one(),
+            1 => T::one(),
+            _ => T::zero(),
+        }
+    }
+}
+
+/** Parses a direction from either a single `UDLR`, `^v<>` or
+    `NESW` character, or a diagonal like `NE` or `SW` */
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NE" => Ok(NE),
+            "SE" => Ok(SE),
+            "SW" => Ok(SW),
+            "NW" => Ok(NW),
+            _ => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Self::from_char(ch),
+                    _ => None,
+                }.ok_or_else(|| format!("Not a valid direction: {s}"))
+            }
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(ch: char) -> Result<Self, Self::Error> {
+        Self::from_char(ch).ok_or_else(|| format!("Not a valid direction: {ch}"))
+    }
+}
+
+impl<T: Num + Signed + Copy> From<Direction> for Coords2D<T> {
+    fn from(dir: Direction) -> Self {
+        dir.to_coords()
+    }
+}
+
+impl <T: Num + Signed + Copy> Add<Direction> for Coords2D<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.to_coords()
+    }
+}
+
+impl <T: Num + Signed + Copy> AddAssign<Direction> for Coords2D<T> {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T> Index<Direction> for [T; 8] {
+    type Output = T;
+
+    fn index(&self, dir: Direction) -> &Self::Output {
+        &self[dir.index()]
+    }
+}
+
+impl<T> IndexMut<Direction> for [T; 8] {
+    fn index_mut(&mut self, dir: Direction) -> &mut Self::Output {
+        &mut self[dir.index()]
+    }
+}
+
+impl<T> Index<Direction> for [T; 4] {
+    type Output = T;
+
+    fn index(&self, dir: Direction) -> &Self::Output {
+        &self[dir.cardinal_index()]
+    }
+}
+
+impl<T> IndexMut<Direction> for [T; 4] {
+    fn index_mut(&mut self, dir: Direction) -> &mut Self::Output {
+        &mut self[dir.cardinal_index()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotate_wraps_around() {
+        assert_eq!(N.rotate(1), NE);
+        assert_eq!(N.rotate(-1), NW);
+        assert_eq!(W.rotate(3), NE);
+        assert_eq!(E.rotate(-10), N);
+        assert_eq!(SE.rotate(16), SE);
+        assert_eq!(N.turn_left(), W);
+        assert_eq!(W.turn_right(), N);
+        assert_eq!(SW.opposite(), NE);
+    }
+
+    #[test]
+    fn parse_directions() {
+        assert_eq!("U".parse(), Ok(N));
+        assert_eq!("v".parse(), Ok(S));
+        assert_eq!("W".parse(), Ok(W));
+        assert_eq!(">".parse(), Ok(E));
+        assert_eq!("NW".parse(), Ok(NW));
+        assert_eq!(Direction::try_from('L'), Ok(W));
+
+        for invalid in ["", "x", "UU", "NN", "ne", "N E"] {
+            assert!(invalid.parse::<Direction>().is_err(), "{invalid:?} should not parse");
+        }
+        assert!(Direction::try_from('?').is_err());
+    }
+
+    #[test]
+    fn index_arrays_by_direction() {
+        let mut all = [0; 8];
+        all[SW] = 5;
+        assert_eq!(all[SW], 5);
+        assert_eq!(all[5], 5);
+
+        let mut cardinal = ['a', 'b', 'c', 'd'];
+        cardinal[W] = 'x';
+        assert_eq!(cardinal, ['a', 'b', 'c', 'x']);
+        assert_eq!(cardinal[E], 'b');
+    }
+
+    #[test]
+    fn parse_coords3d() {
+        assert_eq!("1,-2,3".parse(), Ok(Coords3D::new(1, -2, 3)));
+        assert_eq!(" 19, 13,  30 ".parse(), Ok(Coords3D::new(19, 13, 30)));
+        assert_eq!("1.5,0,-2".parse(), Ok(Coords3D::new(1.5, 0.0, -2.0)));
+
+        for invalid in ["", "1,2", "1,2,3,4", "1,,3", "a,b,c", "1;2;3"] {
+            assert!(invalid.parse::<Coords3D<i64>>().is_err(), "{invalid:?} should not parse");
+        }
+        assert!("-1,2,3".parse::<Coords3D<u32>>().is_err());
+    }
+
+    #[test]
+    fn neighbors3d() {
+        let pos = Coords3D::new(1, 2, 3);
+        let face = pos.neighbors();
+        let all = pos.neighbors_diag();
+
+        assert!(face.iter().all(|n| n.manhattan_dist(&pos) == 1));
+        assert!(face.iter().all(|n| all.contains(n)));
+        assert!(!all.contains(&pos));
+
+        let mut unique = all.to_vec();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 26);
+        assert!(all.iter().all(|n| [n.x - pos.x, n.y - pos.y, n.z - pos.z].iter().all(|d| d.abs() <= 1)));
+    }
+
+    #[test]
+    fn cross_product() {
+        let (x, y, z) = (Coords3D::new(1, 0, 0), Coords3D::new(0, 1, 0), Coords3D::new(0, 0, 1));
+        assert_eq!(x.cross(&y), z);
+        assert_eq!(y.cross(&x), -z);
+        assert_eq!(y.cross(&z), x);
+
+        let (a, b) = (Coords3D::new(2, -3, 5), Coords3D::new(-1, 4, 7));
+        let c = a.cross(&b);
+        assert_eq!(c, Coords3D::new(-41, -19, 5));
+        assert_eq!(c.dot(&a), 0);
+        assert_eq!(c.dot(&b), 0);
+        assert_eq!(a.cross(&a), Coords3D::origin());
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a cardinal direction")]
+    fn index_cardinal_array_with_diagonal() {
+        let _ = [0; 4][NE];
+    }
+}
@@ -0,0 +1,211 @@
+#![allow(dead_code)]
+
+use std::hash::Hash;
+use rustc_hash::FxHashMap;
+
+/** Describes a sequence of states s0, s1, s2... that eventually repeats
+    itself, where s(i + period) == s(i) for every i >= offset */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub offset: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /** The earliest step whose state is the same as the one at step `n` */
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.offset {
+            n
+        } else {
+            self.offset + (n - self.offset) % self.period
+        }
+    }
+}
+
+/** Finds the cycle in the sequence of states generated by repeatedly
+    applying a step function, remembering every state in a hash map */
+pub fn find_cycle<S, F>(start: S, step: F) -> Cycle
+where S: Clone + Hash + Eq,
+      F: FnMut(&S) -> S
+{
+    find_cycle_by_key(start, step, S::clone)
+}
+
+/** Same as `find_cycle`, but only remembers a key computed from every state.
+    Two states with the same key must always lead to the same future keys. */
+pub fn find_cycle_by_key<S, K, F, P>(start: S, step: F, key: P) -> Cycle
+where K: Hash + Eq,
+      F: FnMut(&S) -> S,
+      P: Fn(&S) -> K
+{
+    find_cycle_within_by_key(start, step, key, usize::MAX).unwrap()
+}
+
+/** Same as `find_cycle_by_key`, but gives up if no state repeats after
+    applying the step function the given amount of times */
+pub fn find_cycle_within_by_key<S, K, F, P>(start: S, step: F, key: P, steps: usize) -> Option<Cycle>
+where K: Hash + Eq,
+      F: FnMut(&S) -> S,
+      P: Fn(&S) -> K
+{
+    let mut seen = FxHashMap::default();
+    let mut current = start;
+    let mut step = step;
+    let mut i = 0;
+
+    loop {
+        if let Some(prev) = seen.insert(key(&current), i) {
+            break Some(Cycle { offset: prev, period: i - prev });
+        }
+        if i == steps {
+            break None;
+        }
+        current = step(&current);
+        i += 1;
+    }
+}
+
+/** Computes the state at step `n` of the sequence generated by a step
+    function, skipping ahead as soon as a repetition is found */
+pub fn state_at<S, F>(start: S, step: F, n: usize) -> S
+where S: Clone + Hash + Eq,
+      F: FnMut(&S) -> S
+{
+    state_at_by_key(start, step, S::clone, n)
+}
+
+/** Same as `state_at`, but detecting the cycle using a key computed from
+    every state, with the same requirements as in `find_cycle_by_key` */
+pub fn state_at_by_key<S, K, F, P>(start: S, step: F, key: P, n: usize) -> S
+where K: Hash + Eq,
+      F: FnMut(&S) -> S,
+      P: Fn(&S) -> K
+{
+    let mut seen = FxHashMap::default();
+    let mut states = vec![start];
+    let mut step = step;
+
+    for i in 0..n {
+        if let Some(prev) = seen.insert(key(&states[i]), i) {
+            let cycle = Cycle { offset: prev, period: i - prev };
+            return states.swap_remove(cycle.equivalent_step(n));
+        }
+        let next = step(&states[i]);
+        states.push(next);
+    }
+
+    states.swap_remove(n)
+}
+
+/** Finds the cycle using Floyd's tortoise and hare algorithm, which only
+    keeps a couple of states in memory at the cost of more steps */
+// https://en.wikipedia.org/wiki/Cycle_detection#Floyd's_tortoise_and_hare
+pub fn find_cycle_floyd<S, F>(start: S, step: F) -> Cycle
+where S: Eq,
+      F: FnMut(&S) -> S
+{
+    let mut step = step;
+    let mut tortoise = step(&start);
+    let mut hare = step(&tortoise);
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let next = step(&hare);
+        hare = step(&next);
+    }
+
+    // The distance between both is now a multiple of the period,
+    // so they meet again at the start of the cycle
+    let mut offset = 0;
+    tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+    }
+
+    let mut period = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        period += 1;
+    }
+
+    Cycle { offset, period }
+}
+
+/** Finds the cycle using Brent's algorithm, which also keeps only a couple
+    of states in memory but usually needs fewer steps than Floyd's */
+// https://en.wikipedia.org/wiki/Cycle_detection#Brent's_algorithm
+pub fn find_cycle_brent<S, F>(start: S, step: F) -> Cycle
+where S: Clone + Eq,
+      F: FnMut(&S) -> S
+{
+    let mut step = step;
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+
+    // Find the period by teleporting the tortoise to the hare every power of two
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Move the hare one period ahead and advance both until they meet
+    let mut offset = 0;
+    tortoise = start.clone();
+    hare = start;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        offset += 1;
+    }
+
+    Cycle { offset, period }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> ... -> lead_in -> ... -> lead_in + period - 1 -> lead_in -> ...
+    fn sequence(lead_in: u32, period: u32) -> impl Fn(&u32) -> u32 {
+        move |&x| if x + 1 == lead_in + period { lead_in } else { x + 1 }
+    }
+
+    #[test]
+    fn all_detectors_agree() {
+        for (lead_in, period) in [(0, 1), (0, 7), (1, 1), (3, 5), (10, 2), (6, 13)] {
+            let expected = Cycle { offset: lead_in as usize, period: period as usize };
+
+            assert_eq!(find_cycle(0, sequence(lead_in, period)), expected);
+            assert_eq!(find_cycle_floyd(0, sequence(lead_in, period)), expected);
+            assert_eq!(find_cycle_brent(0, sequence(lead_in, period)), expected);
+        }
+    }
+
+    #[test]
+    fn state_at_skips_ahead() {
+        let step = sequence(4, 6);
+        assert_eq!(state_at(0, &step, 3), 3);
+        assert_eq!(state_at(0, &step, 1_000_000_001), 5);
+        assert_eq!(Cycle { offset: 4, period: 6 }.equivalent_step(16), 4);
+    }
+
+    #[test]
+    fn find_cycle_within_gives_up() {
+        let step = sequence(3, 5);
+        assert_eq!(find_cycle_within_by_key(0, &step, |&x| x, 7), None);
+        assert_eq!(find_cycle_within_by_key(0, &step, |&x| x, 8), Some(Cycle { offset: 3, period: 5 }));
+    }
+}
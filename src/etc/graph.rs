@@ -0,0 +1,323 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+use num_traits::Zero;
+use petgraph::algo::{dijkstra, dominators, tarjan_scc, toposort, Measure};
+use petgraph::dot::Dot;
+use petgraph::prelude::{Directed, EdgeIndex, EdgeRef, Graph, NodeIndex};
+use petgraph::visit::{Dfs, Walker};
+use petgraph::EdgeType;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+/** A petgraph graph whose nodes are identified by unique labels, which
+    takes care of mapping every label to its node index. The label of
+    every node is also stored as its weight. */
+#[derive(Clone, Debug)]
+pub struct LabeledGraph<L, E, Ty: EdgeType = Directed> {
+    graph: Graph<L, E, Ty>,
+    index: FxHashMap<L, NodeIndex>,
+}
+
+impl<L: Hash + Eq + Clone, E, Ty: EdgeType> LabeledGraph<L, E, Ty> {
+    pub fn new() -> Self {
+        Self { graph: Graph::default(), index: FxHashMap::default() }
+    }
+
+    /** Builds a graph from a list of (from, to, weight) edges,
+        adding the nodes as they first appear */
+    pub fn from_edges<I: IntoIterator<Item = (L, L, E)>>(edges: I) -> Self {
+        let mut res = Self::new();
+        for (from, to, weight) in edges {
+            res.add_edge(from, to, weight);
+        }
+        res
+    }
+
+    /** Returns the index of the node with a label, adding it if needed */
+    pub fn node(&mut self, label: L) -> NodeIndex {
+        if let Some(&ix) = self.index.get(&label) {
+            return ix;
+        }
+
+        let ix = self.graph.add_node(label.clone());
+        self.index.insert(label, ix);
+        ix
+    }
+
+    pub fn add_edge(&mut self, from: L, to: L, weight: E) -> EdgeIndex {
+        let (a, b) = (self.node(from), self.node(to));
+        self.graph.add_edge(a, b, weight)
+    }
+
+    pub fn index_of(&self, label: &L) -> Option<NodeIndex> {
+        self.index.get(label).copied()
+    }
+
+    pub fn label(&self, ix: NodeIndex) -> &L {
+        &self.graph[ix]
+    }
+
+    pub fn graph(&self) -> &Graph<L, E, Ty> {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph<L, E, Ty> {
+        self.graph
+    }
+
+    /** Splits the builder into the graph and the label-to-index map */
+    pub fn into_parts(self) -> (Graph<L, E, Ty>, FxHashMap<L, NodeIndex>) {
+        (self.graph, self.index)
+    }
+}
+
+impl<L: Hash + Eq + Clone, E, Ty: EdgeType> Default for LabeledGraph<L, E, Ty> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<L: Display, E: Display, Ty: EdgeType> LabeledGraph<L, E, Ty> {
+    /** Renders the graph in Graphviz DOT format */
+    pub fn to_dot(&self) -> String {
+        to_dot(&self.graph)
+    }
+}
+
+/** Renders a graph in Graphviz DOT format, using the node
+    and edge weights as their labels */
+pub fn to_dot<N: Display, E: Display, Ty: EdgeType>(graph: &Graph<N, E, Ty>) -> String {
+    format!("{}", Dot::new(graph))
+}
+
+/** All the nodes that can be reached from a starting one, including itself */
+pub fn reachable_from<N, E, Ty: EdgeType>(graph: &Graph<N, E, Ty>, start: NodeIndex) -> FxHashSet<NodeIndex> {
+    Dfs::new(graph, start).iter(graph).collect()
+}
+
+/** Orders the nodes so that every edge goes from an earlier node
+    to a later one, or returns None if the graph has a cycle */
+pub fn topological_order<N, E>(graph: &Graph<N, E>) -> Option<Vec<NodeIndex>> {
+    toposort(graph, None).ok()
+}
+
+/** The strongly connected components of the graph, in reverse topological
+    order. Every node in a component can reach all the others in it. */
+pub fn strongly_connected_components<N, E>(graph: &Graph<N, E>) -> Vec<Vec<NodeIndex>> {
+    tarjan_scc(graph)
+}
+
+/** The immediate dominator of every node reachable from a root. A node
+    dominates another if every path from the root to the latter goes through
+    the former. The root itself has no immediate dominator. */
+pub fn immediate_dominators<N, E>(graph: &Graph<N, E>, root: NodeIndex) -> FxHashMap<NodeIndex, NodeIndex> {
+    let doms = dominators::simple_fast(graph, root);
+    graph.node_indices()
+        .filter_map(|node| doms.immediate_dominator(node).map(|dom| (node, dom)))
+        .collect()
+}
+
+/** For every node reachable from a root, how many other nodes it dominates,
+    that is, how many nodes would become unreachable if it was removed */
+pub fn dominated_counts<N, E>(graph: &Graph<N, E>, root: NodeIndex) -> FxHashMap<NodeIndex, usize> {
+    let idoms = immediate_dominators(graph, root);
+    let mut counts: FxHashMap<_, _> = reachable_from(graph, root).into_iter().map(|node| (node, 0)).collect();
+
+    // Every node counts towards all of its dominators
+    for &node in idoms.keys() {
+        let mut current = node;
+        while let Some(&dom) = idoms.get(&current) {
+            *counts.get_mut(&dom).unwrap() += 1;
+            current = dom;
+        }
+    }
+
+    counts
+}
+
+/** The length of the shortest path from a node to every node reachable from it */
+pub fn shortest_distances<N, E, Ty>(graph: &Graph<N, E, Ty>, start: NodeIndex) -> FxHashMap<NodeIndex, E>
+where E: Measure + Copy,
+      Ty: EdgeType
+{
+    dijkstra(graph, start, None, |edge| *edge.weight()).into_iter().collect()
+}
+
+/** The length of the longest path between two nodes of a directed acyclic
+    graph, or None if the end can't be reached. Panics if there is a cycle. */
+pub fn longest_path_dag<N, E>(graph: &Graph<N, E>, start: NodeIndex, end: NodeIndex) -> Option<E>
+where E: Copy + Ord + Zero
+{
+    let order = topological_order(graph).expect("The graph must not have cycles");
+    let mut best: FxHashMap<NodeIndex, E> = FxHashMap::default();
+    best.insert(start, E::zero());
+
+    for node in order {
+        let Some(&dist) = best.get(&node) else { continue };
+        for edge in graph.edges(node) {
+            let candidate = dist + *edge.weight();
+            let entry = best.entry(edge.target()).or_insert(candidate);
+            *entry = (*entry).max(candidate);
+        }
+    }
+
+    best.get(&end).copied()
+}
+
+/** The length of the longest path between two nodes that never visits the
+    same node twice, in any kind of graph. This takes exponential time, so
+    it is only viable for graphs with a few dozen nodes. */
+pub fn longest_simple_path<N, E, Ty>(graph: &Graph<N, E, Ty>, start: NodeIndex, end: NodeIndex) -> Option<E>
+where E: Copy + Ord + Zero,
+      Ty: EdgeType
+{
+    let mut visited = vec![false; graph.node_count()];
+    longest_simple_path_rec(graph, start, end, &mut visited)
+}
+
+fn longest_simple_path_rec<N, E, Ty>(graph: &Graph<N, E, Ty>, current: NodeIndex, end: NodeIndex, visited: &mut [bool]) -> Option<E>
+where E: Copy + Ord + Zero,
+      Ty: EdgeType
+{
+    if current == end {
+        return Some(E::zero());
+    }
+
+    visited[current.index()] = true;
+    let mut best = None;
+
+    for edge in graph.edges(current) {
+        if visited[edge.target().index()] {
+            continue;
+        }
+
+        if let Some(len) = longest_simple_path_rec(graph, edge.target(), end, visited) {
+            best = best.max(Some(len + *edge.weight()));
+        }
+    }
+
+    visited[current.index()] = false;
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use petgraph::Undirected;
+
+    type Labeled = LabeledGraph<&'static str, u32>;
+
+    // a -> b -> e is the longest path, a -> b -> d -> e the shortest
+    fn diamond() -> Labeled {
+        LabeledGraph::from_edges([("a", "b", 1), ("a", "c", 5), ("b", "d", 1), ("c", "d", 1),
+                                  ("d", "e", 2), ("b", "e", 10), ("f", "a", 1)])
+    }
+
+    fn ix(graph: &Labeled, label: &str) -> NodeIndex {
+        graph.index_of(&label).unwrap()
+    }
+
+    fn labels(graph: &Labeled, nodes: &[NodeIndex]) -> Vec<&'static str> {
+        let mut res = nodes.iter().map(|&node| *graph.label(node)).collect::<Vec<_>>();
+        res.sort();
+        res
+    }
+
+    #[test]
+    fn topological_order_of_dag() {
+        let g = diamond();
+        let order = topological_order(g.graph()).unwrap();
+        let position = |node| order.iter().position(|&n| n == node).unwrap();
+
+        assert_eq!(order.len(), 6);
+        assert!(g.graph().edge_references().all(|edge| position(edge.source()) < position(edge.target())));
+    }
+
+    #[test]
+    fn paths_in_dag() {
+        let g = diamond();
+        let (a, e, f) = (ix(&g, "a"), ix(&g, "e"), ix(&g, "f"));
+
+        assert_eq!(longest_path_dag(g.graph(), a, e), Some(11));
+        assert_eq!(longest_path_dag(g.graph(), a, f), None);
+        assert_eq!(longest_path_dag(g.graph(), a, a), Some(0));
+
+        let dist = shortest_distances(g.graph(), a);
+        assert_eq!(dist[&e], 4);
+        assert_eq!(dist[&ix(&g, "c")], 5);
+        assert_eq!(dist[&a], 0);
+        assert!(!dist.contains_key(&f));
+    }
+
+    #[test]
+    fn cycles_have_no_topological_order() {
+        let g = LabeledGraph::from_edges([("a", "b", 1), ("b", "c", 1), ("c", "a", 1)]);
+        assert_eq!(topological_order(g.graph()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not have cycles")]
+    fn longest_path_dag_rejects_cycles() {
+        let g: Labeled = LabeledGraph::from_edges([("a", "b", 1), ("b", "a", 1)]);
+        longest_path_dag(g.graph(), ix(&g, "a"), ix(&g, "b"));
+    }
+
+    #[test]
+    fn strongly_connected() {
+        let mut g: Labeled = LabeledGraph::from_edges([("1", "2", 0), ("2", "3", 0), ("3", "1", 0),
+                                                       ("3", "4", 0), ("4", "5", 0), ("5", "4", 0)]);
+        g.node("6");
+
+        let components = strongly_connected_components(g.graph());
+        let components = components.iter().map(|comp| labels(&g, comp)).collect::<Vec<_>>();
+        assert_eq!(components.len(), 3);
+
+        // Components that are reached from others come first
+        let position = |comp: &[&str]| components.iter().position(|c| c == comp).unwrap();
+        assert!(position(&["4", "5"]) < position(&["1", "2", "3"]));
+        assert!(components.contains(&vec!["6"]));
+    }
+
+    #[test]
+    fn dominators() {
+        // Every path from r to the rest goes through x, and to v through w
+        let g: Labeled = LabeledGraph::from_edges([("r", "x", 1), ("x", "y", 1), ("x", "z", 1),
+                                                   ("y", "w", 1), ("z", "w", 1), ("w", "v", 1), ("q", "w", 1)]);
+        let idoms = immediate_dominators(g.graph(), ix(&g, "r"));
+        assert_eq!(g.label(idoms[&ix(&g, "w")]), &"x");
+        assert_eq!(g.label(idoms[&ix(&g, "v")]), &"w");
+        assert!(!idoms.contains_key(&ix(&g, "r")));
+        assert!(!idoms.contains_key(&ix(&g, "q")));
+
+        let counts = dominated_counts(g.graph(), ix(&g, "r"));
+        let count = |label| counts[&ix(&g, label)];
+        assert_eq!([count("r"), count("x"), count("w"), count("y"), count("v")], [5, 4, 1, 0, 0]);
+        assert!(!counts.contains_key(&ix(&g, "q")));
+    }
+
+    #[test]
+    fn longest_simple_path_in_undirected_graph() {
+        // A square with a heavy diagonal from a to c
+        let g: LabeledGraph<_, u32, Undirected> = LabeledGraph::from_edges([
+            ("a", "b", 1), ("b", "c", 1), ("c", "d", 1), ("d", "a", 1), ("a", "c", 5)]);
+        let (a, b, d) = (g.index_of(&"a").unwrap(), g.index_of(&"b").unwrap(), g.index_of(&"d").unwrap());
+
+        assert_eq!(longest_simple_path(g.graph(), a, d), Some(6));
+        assert_eq!(longest_simple_path(g.graph(), b, d), Some(7));
+
+        let mut disconnected = g.clone();
+        let e = disconnected.node("e");
+        assert_eq!(longest_simple_path(disconnected.graph(), a, e), None);
+    }
+
+    #[test]
+    fn dot_output() {
+        let g: Labeled = LabeledGraph::from_edges([("a", "b", 7)]);
+        let dot = g.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("label = \"a\""));
+        assert!(dot.contains("0 -> 1 [ label = \"7\" ]"));
+    }
+}
@@ -0,0 +1,155 @@
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+use num_traits::int::PrimInt;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+type Pos = Coords2D<i64>;
+
+// Breadth-first searches over a VecMat, moving orthogonally between cells.
+// In all of them, the starting positions are considered reachable at distance
+// zero even if they are not passable themselves, which allows searching from
+// special tiles such as an 'S' in the input.
+
+impl<T: Copy> VecMat<T> {
+    /** Returns a mask with all the positions reachable from
+        the starting one through passable cells */
+    pub fn flood_fill<I, F>(&self, start: Coords2D<I>, passable: F) -> VecMat<bool>
+    where I: PrimInt,
+          F: Fn(T) -> bool
+    {
+        self.bfs_distances(start, passable).map(|dist| dist.is_some())
+    }
+
+    /** Computes the minimum amount of steps needed to reach every cell
+        from the starting position, or `None` for unreachable cells */
+    pub fn bfs_distances<I, F>(&self, start: Coords2D<I>, passable: F) -> VecMat<Option<u32>>
+    where I: PrimInt,
+          F: Fn(T) -> bool
+    {
+        self.multi_bfs_distances([start], passable)
+    }
+
+    /** Computes the minimum amount of steps needed to reach every cell
+        from the closest of several starting positions */
+    pub fn multi_bfs_distances<I, S, F>(&self, starts: S, passable: F) -> VecMat<Option<u32>>
+    where I: PrimInt,
+          S: IntoIterator<Item = Coords2D<I>>,
+          F: Fn(T) -> bool
+    {
+        let mut distances = VecMat::new(self.width(), self.height(), None);
+        let mut queue = VecDeque::new();
+
+        for start in starts {
+            let pos = Pos::new(start.x.to_i64().unwrap(), start.y.to_i64().unwrap());
+            if distances[pos].is_none() {
+                distances[pos] = Some(0);
+                queue.push_back(pos);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            let next_dist = distances[pos].unwrap() + 1;
+
+            for (next, val) in self.neighbors4(pos) {
+                if passable(val) && distances[next].is_none() {
+                    distances[next] = Some(next_dist);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distances
+    }
+
+    /** Labels every passable cell with the ID of the connected region
+        it belongs to, numbered from 0 in row-major order of their first
+        cell. Returns the labels and the total amount of regions. */
+    pub fn connected_components<F>(&self, passable: F) -> (VecMat<Option<usize>>, usize)
+    where F: Fn(T) -> bool
+    {
+        let mut labels = VecMat::new(self.width(), self.height(), None);
+        let mut count = 0;
+
+        for (start, val) in self.indexed_iter::<i64>() {
+            if !passable(val) || labels[start].is_some() {
+                continue;
+            }
+
+            labels[start] = Some(count);
+            let mut queue = VecDeque::from([start]);
+
+            while let Some(pos) = queue.pop_front() {
+                for (next, val) in self.neighbors4(pos) {
+                    if passable(val) && labels[next].is_none() {
+                        labels[next] = Some(count);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            count += 1;
+        }
+
+        (labels, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // S.#.
+    // ..#.
+    // ###.
+    fn maze() -> VecMat<char> {
+        VecMat::from_str("S.#.\n..#.\n###.")
+    }
+
+    fn open(c: char) -> bool {
+        c == '.'
+    }
+
+    #[test]
+    fn unreachable_cells_have_no_distance() {
+        let dist = maze().bfs_distances(Coords2D::new(0, 0), open);
+        assert_eq!(dist[(0, 0)], Some(0));
+        assert_eq!(dist[(1, 1)], Some(2));
+        assert_eq!(dist[(3, 0)], None);
+        assert_eq!(dist[(2, 0)], None);
+
+        let reachable = maze().flood_fill(Coords2D::new(0, 0), open);
+        assert_eq!(reachable.indexed_iter::<i64>().filter(|&(_, r)| r).count(), 4);
+    }
+
+    #[test]
+    fn impassable_start_is_still_reached() {
+        // The start is a wall, but its passable neighbors are reached from it
+        let dist = maze().bfs_distances(Coords2D::new(2, 1), open);
+        assert_eq!(dist[(2, 1)], Some(0));
+        assert_eq!(dist[(1, 1)], Some(1));
+        assert_eq!(dist[(3, 1)], Some(1));
+        assert_eq!(dist[(0, 0)], None);
+    }
+
+    #[test]
+    fn several_sources_use_the_closest_one() {
+        let mat = VecMat::new(7, 1, '.');
+        let dist = mat.multi_bfs_distances([Coords2D::new(0, 0), Coords2D::new(5, 0)], open);
+        assert_eq!(dist.get_row(0), vec![Some(0), Some(1), Some(2), Some(2), Some(1), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn components_are_numbered_in_row_major_order() {
+        let (labels, count) = maze().connected_components(open);
+        assert_eq!(count, 2);
+        assert_eq!(labels[(1, 0)], Some(0));
+        assert_eq!(labels[(3, 0)], Some(1));
+        assert_eq!(labels[(3, 2)], Some(1));
+        assert_eq!(labels[(0, 0)], None);
+        assert_eq!(labels[(0, 1)], Some(0));
+    }
+}
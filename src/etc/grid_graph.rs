@@ -0,0 +1,146 @@
+#![allow(dead_code)]
+
+use std::hash::Hash;
+
+use num_traits::int::PrimInt;
+use petgraph::prelude::{Graph, NodeIndex};
+use rustc_hash::FxHashMap;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+type Pos = Coords2D<i64>;
+
+/** A graph whose nodes are the junctions of a grid maze,
+    connected by edges weighted with the length of the corridors between them */
+pub type CorridorGraph<I> = Graph<Coords2D<I>, u32>;
+pub type CorridorIndex<I> = FxHashMap<Coords2D<I>, NodeIndex>;
+
+/** Collapses the corridors of a maze into a graph of junctions. The nodes
+    are all passable cells that don't have exactly two passable neighbors
+    (forks and dead ends), plus any additional key points provided. Every
+    corridor produces an edge in each direction between the nodes on its ends. */
+pub fn compress_grid<I, T, P>(grid: &VecMat<T>, passable: P, key_points: &[Coords2D<I>]) -> (CorridorGraph<I>, CorridorIndex<I>)
+where I: PrimInt + Hash,
+      T: Copy,
+      P: Fn(T) -> bool
+{
+    compress_grid_one_way(grid, passable, |_| None, key_points)
+}
+
+/** Like `compress_grid`, but supporting one-way tiles. When a tile maps
+    to a direction, the only way to leave it is by moving in that direction,
+    so corridors that go through it against its direction produce no edge.
+    Corridors that lead back to the node they started from, such as a loop
+    with a single key point on it, produce no edge either. */
+pub fn compress_grid_one_way<I, T, P, S>(grid: &VecMat<T>, passable: P, one_way: S, key_points: &[Coords2D<I>]) -> (CorridorGraph<I>, CorridorIndex<I>)
+where I: PrimInt + Hash,
+      T: Copy,
+      P: Fn(T) -> bool,
+      S: Fn(T) -> Option<Coords2D<I>>
+{
+    let to_i64 = |pos: Coords2D<I>| Pos::new(pos.x.to_i64().unwrap(), pos.y.to_i64().unwrap());
+    let from_i64 = |pos: Pos| Coords2D::new(I::from(pos.x).unwrap(), I::from(pos.y).unwrap());
+
+    let passable_neighbors = |pos: Pos| grid.neighbors4(pos)
+        .filter(|(_, tile)| passable(*tile))
+        .map(|(next, _)| next);
+
+    let can_step = |from: Pos, to: Pos| match one_way(grid[from]) {
+        Some(dir) => to_i64(dir) == to - from,
+        None => true,
+    };
+
+    let mut graph = CorridorGraph::new();
+    let mut id_map = FxHashMap::default();
+
+    let junctions = grid.indexed_iter::<i64>()
+        .filter(|&(pos, tile)| passable(tile) && passable_neighbors(pos).count() != 2)
+        .map(|(pos, _)| pos)
+        .chain(key_points.iter().map(|&pos| to_i64(pos)));
+
+    for pos in junctions {
+        id_map.entry(pos).or_insert_with(|| graph.add_node(from_i64(pos)));
+    }
+
+    for (&node, &node_ix) in id_map.iter() {
+        for first_step in passable_neighbors(node) {
+            // Walk along the corridor until another node is found,
+            // or give up if a one-way tile blocks the way
+            let (mut prev, mut current, mut len) = (node, first_step, 1);
+            let mut blocked = !can_step(prev, current);
+
+            while !blocked && !id_map.contains_key(&current) {
+                let next = passable_neighbors(current).find(|&n| n != prev).unwrap();
+                blocked = !can_step(current, next);
+                (prev, current, len) = (current, next, len + 1);
+            }
+
+            if !blocked && current != node {
+                graph.add_edge(node_ix, id_map[&current], len);
+            }
+        }
+    }
+
+    let index = id_map.into_iter().map(|(pos, ix)| (from_i64(pos), ix)).collect();
+    (graph, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn passable(c: char) -> bool {
+        c != '#'
+    }
+
+    fn slopes(c: char) -> Option<Coords2D<i32>> {
+        match c {
+            '>' => Some(Coords2D::new(1, 0)),
+            '<' => Some(Coords2D::new(-1, 0)),
+            _ => None,
+        }
+    }
+
+    // Lengths of the edges from one position to another
+    fn lengths(graph: &CorridorGraph<i32>, index: &CorridorIndex<i32>, from: (i32, i32), to: (i32, i32)) -> Vec<u32> {
+        let (from, to) = (index[&Coords2D::new(from.0, from.1)], index[&Coords2D::new(to.0, to.1)]);
+        graph.edges_connecting(from, to).map(|edge| *edge.weight()).collect()
+    }
+
+    #[test]
+    fn dead_ends_are_nodes() {
+        let grid = VecMat::from_str(".....");
+        let (graph, index) = compress_grid::<i32, _, _>(&grid, passable, &[]);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(lengths(&graph, &index, (0, 0), (4, 0)), vec![4]);
+        assert_eq!(lengths(&graph, &index, (4, 0), (0, 0)), vec![4]);
+    }
+
+    #[test]
+    fn parallel_corridors_produce_parallel_edges() {
+        let grid = VecMat::from_str(".....\n.###.\n.....");
+        let keys = [Coords2D::new(0, 1), Coords2D::new(4, 1)];
+        let (graph, index) = compress_grid(&grid, passable, &keys);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(lengths(&graph, &index, (0, 1), (4, 1)), vec![6, 6]);
+        assert_eq!(lengths(&graph, &index, (4, 1), (0, 1)), vec![6, 6]);
+    }
+
+    #[test]
+    fn one_way_tiles_block_corridors() {
+        let grid = VecMat::from_str("..<..");
+        let (graph, index) = compress_grid_one_way(&grid, passable, slopes, &[]);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(lengths(&graph, &index, (4, 0), (0, 0)), vec![4]);
+    }
+
+    #[test]
+    fn loops_back_to_the_same_node_are_dropped() {
+        let grid = VecMat::from_str(".....\n.###.\n.....");
+        let (graph, index) = compress_grid(&grid, passable, &[Coords2D::new(0, 1)]);
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(graph.edge_count(), 0);
+        assert!(index.contains_key(&Coords2D::new(0, 1)));
+    }
+}
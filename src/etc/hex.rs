@@ -0,0 +1,333 @@
+#![allow(dead_code)]
+
+use std::ops::{Add, Sub, AddAssign, SubAssign, Mul, Neg};
+use num_traits::{Num, PrimInt, Signed, ToPrimitive};
+
+/** Axial coordinates in a hexagonal grid. The third cube coordinate
+    is implicit, since `q + r + s = 0` always holds. */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HexCoords<T: Num> {
+    pub q: T,
+    pub r: T,
+}
+
+/** How the hexagons are laid out, which determines the names of
+    the directions and the conversion to pixel coordinates */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HexOrientation {
+    /** Flat sides on top, neighbors are `n, ne, se, s, sw, nw` */
+    FlatTop,
+    /** Pointy corners on top, neighbors are `e, ne, nw, w, sw, se` */
+    PointyTop,
+}
+
+impl<T: Num> HexCoords<T> {
+    pub const fn new(q: T, r: T) -> Self {
+        Self { q, r }
+    }
+
+    pub fn origin() -> Self {
+        (T::zero(), T::zero()).into()
+    }
+}
+
+impl<T: Num + Signed + Copy> HexCoords<T> {
+    /** Builds axial coordinates from cube ones, which must add up to 0 */
+    pub fn from_cube(q: T, r: T, s: T) -> Self {
+        assert!((q + r + s).is_zero(), "Cube coordinates must add up to 0");
+        Self::new(q, r)
+    }
+
+    /** The implicit third cube coordinate */
+    pub fn s(&self) -> T {
+        -self.q - self.r
+    }
+
+    pub fn cube(&self) -> (T, T, T) {
+        (self.q, self.r, self.s())
+    }
+
+    /** The 6 unit vectors, in counter-clockwise order starting from
+        `e` in the pointy-top layout (or `se` in the flat-top one) */
+    pub fn directions() -> [Self; 6] {
+        let (zero, one) = (T::zero(), T::one());
+        [Self::new(one, zero), Self::new(one, -one), Self::new(zero, -one),
+         Self::new(-one, zero), Self::new(-one, one), Self::new(zero, one)]
+    }
+
+    pub fn neighbors(&self) -> [Self; 6] {
+        Self::directions().map(|dir| *self + dir)
+    }
+
+    /** Parses a single direction like `ne` or `s` into its unit vector */
+    pub fn parse_direction(dir: &str, orientation: HexOrientation) -> Option<Self> {
+        let [e, ne, nw, w, sw, se] = Self::directions();
+
+        match (orientation, dir.trim()) {
+            (_, "ne") => Some(ne),
+            (_, "sw") => Some(sw),
+            (HexOrientation::PointyTop, "e") => Some(e),
+            (HexOrientation::PointyTop, "w") => Some(w),
+            (HexOrientation::PointyTop, "nw") => Some(nw),
+            (HexOrientation::PointyTop, "se") => Some(se),
+            (HexOrientation::FlatTop, "se") => Some(e),
+            (HexOrientation::FlatTop, "nw") => Some(w),
+            (HexOrientation::FlatTop, "n") => Some(nw),
+            (HexOrientation::FlatTop, "s") => Some(se),
+            _ => None,
+        }
+    }
+
+    /** Parses a sequence of moves, which may be separated by commas
+        like `ne,se,n` or written together like `nwwswee`. Only the latter
+        form needs to split two-letter directions from the single letters. */
+    pub fn parse_moves(moves: &str, orientation: HexOrientation) -> Result<Vec<Self>, String> {
+        let moves = moves.trim();
+        let parse = |token: &str| Self::parse_direction(token, orientation)
+            .ok_or_else(|| format!("Not a valid hex direction: {}", token.trim()));
+
+        if moves.is_empty() {
+            return Ok(vec![]);
+        } else if moves.contains(',') {
+            return moves.split(',').map(parse).collect();
+        }
+
+        let chars: Vec<char> = moves.chars().collect();
+        let mut res = vec![];
+        let mut i = 0;
+
+        while i < chars.len() {
+            // Two-letter directions always start with n or s and end with e or w
+            let len = match chars.get(i..i + 2) {
+                Some([a, b]) if matches!(a, 'n' | 's') && matches!(b, 'e' | 'w') => 2,
+                _ => 1,
+            };
+
+            let token: String = chars[i..i + len].iter().collect();
+            res.push(parse(&token)?);
+            i += len;
+        }
+
+        Ok(res)
+    }
+
+    /** Rotates the position 60 degrees clockwise around the origin */
+    pub fn rotate_right(&self) -> Self {
+        Self::new(-self.r, -self.s())
+    }
+
+    /** Rotates the position 60 degrees counter-clockwise around the origin */
+    pub fn rotate_left(&self) -> Self {
+        Self::new(-self.s(), -self.q)
+    }
+
+    /** Rotates the position around another one in increments of 60
+        degrees, clockwise for positive amounts */
+    pub fn rotate_around(&self, center: &Self, steps: i32) -> Self {
+        let mut rel = *self - *center;
+        for _ in 0..steps.rem_euclid(6) {
+            rel = rel.rotate_right();
+        }
+        rel + *center
+    }
+
+    /** All the positions at an exact distance from this one */
+    pub fn ring(&self, radius: usize) -> Vec<Self> {
+        if radius == 0 {
+            return vec![*self];
+        }
+
+        let dirs = Self::directions();
+        let mut current = *self;
+        for _ in 0..radius {
+            current += dirs[4];
+        }
+
+        let mut res = Vec::with_capacity(6 * radius);
+
+        for dir in dirs {
+            for _ in 0..radius {
+                res.push(current);
+                current += dir;
+            }
+        }
+
+        res
+    }
+
+    /** All the positions at a distance of at most `radius` from this one,
+        ordered by increasing distance */
+    pub fn spiral(&self, radius: usize) -> Vec<Self> {
+        (0..=radius).flat_map(|r| self.ring(r)).collect()
+    }
+}
+
+impl<T: PrimInt + Signed> HexCoords<T> {
+    /** Amount of steps needed to go from one position to the other */
+    pub fn distance(&self, other: &Self) -> T {
+        let (dq, dr, ds) = (*self - *other).cube();
+        (dq.abs() + dr.abs() + ds.abs()) / T::from(2).unwrap()
+    }
+}
+
+impl<T: Num + Signed + Copy + ToPrimitive> HexCoords<T> {
+    /** The center of the hexagon in pixel coordinates, with y growing downwards,
+        where `size` is the distance from the center to any corner */
+    pub fn to_pixel(self, size: f64, orientation: HexOrientation) -> (f64, f64) {
+        let q = self.q.to_f64().unwrap();
+        let r = self.r.to_f64().unwrap();
+        let sqrt3 = 3f64.sqrt();
+
+        match orientation {
+            HexOrientation::PointyTop => (size * (sqrt3 * q + sqrt3 / 2. * r), size * 1.5 * r),
+            HexOrientation::FlatTop => (size * 1.5 * q, size * (sqrt3 / 2. * q + sqrt3 * r)),
+        }
+    }
+}
+
+impl<T: Num> From<(T, T)> for HexCoords<T> {
+    fn from((q, r): (T, T)) -> Self {
+        Self::new(q, r)
+    }
+}
+
+impl<T: Num> From<HexCoords<T>> for (T, T) {
+    fn from(c: HexCoords<T>) -> (T, T) {
+        (c.q, c.r)
+    }
+}
+
+impl <T: Num> Add<HexCoords<T>> for HexCoords<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl <T: Num + Copy> AddAssign<HexCoords<T>> for HexCoords<T> {
+    fn add_assign(&mut self, rhs: HexCoords<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl <T: Num> Sub<HexCoords<T>> for HexCoords<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl <T: Num + Copy> SubAssign<HexCoords<T>> for HexCoords<T> {
+    fn sub_assign(&mut self, rhs: HexCoords<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl <T: Num + Copy> Mul<T> for HexCoords<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl <T: Num + Neg<Output = T>> Neg for HexCoords<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.q, -self.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use HexOrientation::*;
+
+    type Hex = HexCoords<i32>;
+
+    fn walk(moves: &str, orientation: HexOrientation) -> Hex {
+        Hex::parse_moves(moves, orientation).unwrap().into_iter().fold(Hex::origin(), |acc, dir| acc + dir)
+    }
+
+    #[test]
+    fn flat_top_moves() {
+        // Examples from Advent of Code 2017, day 11
+        assert_eq!(walk("ne,ne,ne", FlatTop).distance(&Hex::origin()), 3);
+        assert_eq!(walk("ne,ne,sw,sw", FlatTop), Hex::origin());
+        assert_eq!(walk("ne,ne,s,s", FlatTop).distance(&Hex::origin()), 2);
+        assert_eq!(walk("se,sw,se,sw,sw", FlatTop).distance(&Hex::origin()), 3);
+    }
+
+    #[test]
+    fn pointy_top_moves() {
+        // Examples from Advent of Code 2020, day 24
+        assert_eq!(walk("nwwswee", PointyTop), Hex::origin());
+        assert_eq!(walk("esew", PointyTop), Hex::parse_direction("se", PointyTop).unwrap());
+    }
+
+    #[test]
+    fn orientation_dependent_directions() {
+        // The same name refers to different neighbors in each layout
+        assert_ne!(Hex::parse_direction("se", FlatTop), Hex::parse_direction("se", PointyTop));
+        assert_ne!(Hex::parse_direction("nw", FlatTop), Hex::parse_direction("nw", PointyTop));
+        assert_eq!(Hex::parse_direction("n", PointyTop), None);
+        assert_eq!(Hex::parse_direction("e", FlatTop), None);
+        assert!(Hex::parse_moves("ne,x", FlatTop).is_err());
+    }
+
+    #[test]
+    fn both_separator_forms() {
+        assert_eq!(walk("ne, se ,n\n", FlatTop), walk("ne,se,n", FlatTop));
+        assert_eq!(Hex::parse_moves("n,s", FlatTop).unwrap().len(), 2);
+        assert_eq!(Hex::parse_moves("e,w,ne", PointyTop).unwrap().len(), 3);
+        assert_eq!(Hex::parse_moves("nwwswee", PointyTop).unwrap().len(), 5);
+        assert_eq!(Hex::parse_moves(" ", PointyTop), Ok(vec![]));
+    }
+
+    #[test]
+    fn separated_letters_are_not_joined() {
+        // Each of these would be a valid diagonal if the comma was dropped
+        for moves in ["n,e", "s,w", "se,s,w"] {
+            assert!(Hex::parse_moves(moves, PointyTop).is_err(), "{moves:?} should not parse");
+        }
+        for moves in ["n,w", "s,e", "n,n,w"] {
+            assert!(Hex::parse_moves(moves, FlatTop).is_err(), "{moves:?} should not parse");
+        }
+        assert!(Hex::parse_moves("ne,", FlatTop).is_err());
+        assert!(Hex::parse_moves("n e", FlatTop).is_err());
+    }
+
+    #[test]
+    fn rings_and_spirals() {
+        let center = Hex::new(3, -1);
+        let ring = center.ring(2);
+
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|pos| pos.distance(&center) == 2));
+        assert_eq!(ring.iter().collect::<std::collections::HashSet<_>>().len(), 12);
+        assert_eq!(center.ring(0), vec![center]);
+        assert_eq!(center.spiral(2).len(), 19);
+    }
+
+    #[test]
+    fn rotations() {
+        let start = Hex::new(2, -3);
+        let mut current = start;
+        for _ in 0..6 {
+            current = current.rotate_right();
+            assert_eq!(current.distance(&Hex::origin()), start.distance(&Hex::origin()));
+        }
+        assert_eq!(current, start);
+
+        let [e, .., se] = Hex::directions();
+        assert_eq!(e.rotate_right(), se);
+        assert_eq!(start.rotate_right().rotate_left(), start);
+
+        let center = Hex::new(1, 1);
+        assert_eq!(start.rotate_around(&center, -1), start.rotate_around(&center, 5));
+        assert_eq!(center.rotate_around(&center, 2), center);
+    }
+}
@@ -1,24 +1,141 @@
 #![allow(dead_code)]
 use rustc_hash::FxHashMap;
 use num_traits::int::PrimInt;
-use std::hash::Hash;
+use std::borrow::Borrow;
+use std::hash::{BuildHasherDefault, Hash};
 
+/** Assigns dense ids to keys in order of first appearance (0, 1, 2...),
+    and keeps track of the key behind every id so it can be looked up back. */
+#[derive(Clone, Debug)]
 pub struct IDAssigner<K: Hash + Eq, V: PrimInt> {
     data: FxHashMap<K, V>,
+    keys: Vec<K>,
 }
 
+/** Interns string slices borrowed from the input */
+pub type StrInterner<'a, V = usize> = IDAssigner<&'a str, V>;
+
 impl<K: Hash + Eq, V: PrimInt> IDAssigner<K, V> {
     pub fn new() -> Self {
-        Self { data: FxHashMap::default() }
+        Self { data: FxHashMap::default(), keys: vec![] }
+    }
+
+    /** Creates an assigner with room for `capacity` keys before reallocating */
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: FxHashMap::with_capacity_and_hasher(capacity, BuildHasherDefault::default()),
+            keys: Vec::with_capacity(capacity),
+        }
+    }
+
+    /** Returns the id of a key without assigning one if it is new */
+    pub fn get<Q>(&self, elem: &Q) -> Option<V>
+    where K: Borrow<Q>,
+          Q: Hash + Eq + ?Sized
+    {
+        self.data.get(elem).copied()
+    }
+
+    pub fn contains<Q>(&self, elem: &Q) -> bool
+    where K: Borrow<Q>,
+          Q: Hash + Eq + ?Sized
+    {
+        self.data.contains_key(elem)
+    }
+
+    /** Returns the key that was assigned an id */
+    pub fn key(&self, id: V) -> Option<&K> {
+        self.keys.get(id.to_usize()?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /** All the keys, in the order of their ids */
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+
+    /** Iterates over all (id, key) pairs in increasing order of id */
+    pub fn iter(&self) -> impl Iterator<Item = (V, &K)> + '_ {
+        self.keys.iter().enumerate().map(|(i, key)| (V::from(i).unwrap(), key))
     }
+}
 
+// Keys are stored both in the map and in the reverse lookup, so they
+// must be cloned. They are usually cheap, like integers or &str.
+impl<K: Hash + Eq + Clone, V: PrimInt> IDAssigner<K, V> {
+    /** Returns the id of a key, assigning the next one if it is new */
     pub fn get_id(&mut self, elem: K) -> V {
         if let Some(id) = self.data.get(&elem) {
             *id
         } else {
-            let next_id = V::from(self.data.len()).unwrap();
-            self.data.insert(elem, next_id);
+            let next_id = V::from(self.keys.len()).unwrap();
+            self.data.insert(elem.clone(), next_id);
+            self.keys.push(elem);
             next_id
         }
     }
 }
+
+impl<K: Hash + Eq, V: PrimInt> Default for IDAssigner<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: PrimInt> FromIterator<K> for IDAssigner<K, V> {
+    fn from_iter<T: IntoIterator<Item = K>>(iter: T) -> Self {
+        let mut res = Self::new();
+        iter.into_iter().for_each(|key| { res.get_id(key); });
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_and_keys_round_trip() {
+        let mut ids: StrInterner = StrInterner::with_capacity(4);
+        assert_eq!(ids.get_id("b"), 0);
+        assert_eq!(ids.get_id("a"), 1);
+        assert_eq!(ids.get_id("b"), 0);
+        assert_eq!(ids.get_id("c"), 2);
+        assert_eq!(ids.len(), 3);
+
+        for (id, key) in ids.iter() {
+            assert_eq!(ids.get(*key), Some(id));
+            assert_eq!(ids.key(id), Some(key));
+        }
+        assert_eq!(ids.keys(), ["b", "a", "c"]);
+        assert_eq!(ids.iter().map(|(id, _)| id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn missing_ids_and_keys() {
+        let ids: IDAssigner<String, u8> = ["x", "y"].into_iter().map(String::from).collect();
+        assert_eq!(ids.get("y"), Some(1));
+        assert_eq!(ids.get("z"), None);
+        assert!(ids.contains("x"));
+        assert_eq!(ids.key(2), None);
+        assert!(IDAssigner::<String, u8>::new().is_empty());
+    }
+
+    #[test]
+    fn keys_without_clone() {
+        // Lookups do not need to clone the keys, only assigning ids does
+        #[derive(Hash, PartialEq, Eq)]
+        struct Opaque;
+
+        let ids: IDAssigner<Opaque, u32> = IDAssigner::default();
+        assert_eq!(ids.get(&Opaque), None);
+        assert_eq!(ids.len(), 0);
+    }
+}
@@ -0,0 +1,112 @@
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::render::Color;
+use super::vecmat::VecMat;
+
+/** An RGB image built from a grid, where every cell
+    is drawn as a square block of pixels */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl GridImage {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /** Raw pixel data, as consecutive RGB triplets in row-major order */
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /** Writes the image in binary PPM (P6) format */
+    pub fn write_ppm<W: Write>(&self, mut out: W) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+        out.write_all(&self.pixels)?;
+        out.flush()
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_ppm(BufWriter::new(File::create(path)?))
+    }
+
+    /** Writes the image in PNG format */
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write>(&self, out: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer.write_image_data(&self.pixels).map_err(io::Error::other)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<T: Copy> VecMat<T> {
+    /** Draws the matrix as an image, mapping every cell to a color
+        and drawing it as a `scale` x `scale` square */
+    pub fn to_image<F: Fn(T) -> Color>(&self, scale: usize, color: F) -> GridImage {
+        assert!(scale > 0, "The scale must be positive");
+        let (width, height) = (self.width() * scale, self.height() * scale);
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for y in 0..self.height() {
+            let row = self.get_row(y).into_iter()
+                .flat_map(|val| {
+                    let (r, g, b) = color(val).rgb();
+                    [r, g, b].repeat(scale)
+                })
+                .collect::<Vec<_>>();
+
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        GridImage { width, height, pixels }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scaled_pixels() {
+        let grid = VecMat::from_str("#.");
+        let image = grid.to_image(2, |ch| if ch == '#' { Color::Rgb(1, 2, 3) } else { Color::Black });
+
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert_eq!(&image.pixels()[..12], &[1, 2, 3, 1, 2, 3, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&image.pixels()[..12], &image.pixels()[12..]);
+    }
+
+    #[test]
+    fn ppm_header_and_length() {
+        let grid = VecMat::new(3, 2, 0);
+        let image = grid.to_image(5, |_| Color::White);
+
+        let mut out = vec![];
+        image.write_ppm(&mut out).unwrap();
+
+        let header = b"P6\n15 10\n255\n";
+        assert!(out.starts_with(header));
+        assert_eq!(out.len(), header.len() + 15 * 10 * 3);
+    }
+}
@@ -0,0 +1,387 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+
+use itertools::Itertools;
+use num_traits::{PrimInt, Signed};
+
+/** A half-open interval of integers [start, end) */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval<T: PrimInt> {
+    pub start: T,
+    pub end: T,
+}
+
+/** A set of integers stored as a sorted list of disjoint intervals.
+    Touching intervals are always merged, so every set has a single
+    representation. */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T: PrimInt> {
+    intervals: Vec<Interval<T>>,
+}
+
+/** A piecewise-linear map over integers. Every piece moves the values in a
+    source interval by a fixed offset, and values outside all the pieces
+    are mapped to themselves. */
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RangeMap<T: PrimInt + Signed> {
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    /** The interval [start, start + len) */
+    pub fn from_len(start: T, len: T) -> Self {
+        Self::new(start, start + len)
+    }
+
+    /** The interval [start, end], with both ends included */
+    pub fn inclusive(start: T, end: T) -> Self {
+        Self::new(start, end + T::one())
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() { T::zero() } else { self.end - self.start }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn contains(&self, val: T) -> bool {
+        self.start <= val && val < self.end
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    /** Moves the whole interval by an offset, which may be negative */
+    pub fn shift(&self, offset: T) -> Self where T: Signed {
+        Self::new(self.start + offset, self.end + offset)
+    }
+}
+
+impl<T: PrimInt + Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    /** The disjoint intervals that make up the set, in increasing order */
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        self.intervals.push(interval);
+        self.normalize();
+    }
+
+    /** Amount of integers in the set */
+    pub fn len(&self) -> T {
+        self.intervals.iter().fold(T::zero(), |acc, i| acc + i.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    pub fn contains(&self, val: T) -> bool {
+        let pos = self.intervals.partition_point(|i| i.end <= val);
+        self.intervals.get(pos).is_some_and(|i| i.contains(val))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().map(|i| i.end - T::one())
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.intervals.iter().chain(&other.intervals).copied().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut res = vec![];
+
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let common = a.intersection(&b);
+            if !common.is_empty() {
+                res.push(common);
+            }
+
+            // Move past whichever interval ends first
+            if a.end < b.end { i += 1 } else { j += 1 }
+        }
+
+        Self { intervals: res }
+    }
+
+    /** The integers in this set that are not in the other one */
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut res = vec![];
+        let mut j = 0;
+
+        for &interval in &self.intervals {
+            let mut current = interval;
+
+            // Skip the intervals of the other set that end before this one starts
+            while j < other.intervals.len() && other.intervals[j].end <= current.start {
+                j += 1;
+            }
+
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].start < current.end {
+                let cut = other.intervals[k];
+                if cut.start > current.start {
+                    res.push(Interval::new(current.start, cut.start));
+                }
+                current.start = current.start.max(cut.end);
+                k += 1;
+            }
+
+            if !current.is_empty() {
+                res.push(current);
+            }
+        }
+
+        Self { intervals: res }
+    }
+
+    // Sorts the intervals, drops the empty ones and merges those that overlap or touch
+    fn normalize(&mut self) {
+        self.intervals.retain(|i| !i.is_empty());
+        self.intervals.sort_unstable();
+
+        self.intervals = self.intervals.iter()
+            .copied()
+            .coalesce(|a, b| if b.start <= a.end {
+                Ok(Interval::new(a.start, a.end.max(b.end)))
+            } else {
+                Err((a, b))
+            })
+            .collect();
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut res = Self { intervals: iter.into_iter().collect() };
+        res.normalize();
+        res
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        std::iter::once(interval).collect()
+    }
+}
+
+impl<T: PrimInt + Signed> RangeMap<T> {
+    /** Creates the identity map */
+    pub fn new() -> Self {
+        Self { pieces: vec![] }
+    }
+
+    /** Maps the values in `source` to consecutive values starting at
+        `dest_start`. Panics if the source overlaps an existing piece.
+        Pieces that map a range to itself are also kept, with an offset
+        of zero, so that later ones are checked against them too. */
+    pub fn add_range(&mut self, source: Interval<T>, dest_start: T) {
+        assert!(self.pieces.iter().all(|(src, _)| !src.overlaps(&source)), "Source ranges must not overlap");
+        if !source.is_empty() {
+            self.pieces.push((source, dest_start - source.start));
+            self.pieces.sort_unstable();
+        }
+    }
+
+    /** The source intervals of all the pieces and the offset they apply */
+    pub fn pieces(&self) -> &[(Interval<T>, T)] {
+        &self.pieces
+    }
+
+    pub fn apply(&self, val: T) -> T {
+        let pos = self.pieces.partition_point(|(src, _)| src.end <= val);
+        match self.pieces.get(pos) {
+            Some((src, offset)) if src.contains(val) => val + *offset,
+            _ => val,
+        }
+    }
+
+    /** Maps every value in a set, returning the set of results */
+    pub fn apply_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        set.intervals().iter()
+            .flat_map(|&interval| self.split(interval))
+            .map(|(part, offset)| part.shift(offset))
+            .collect()
+    }
+
+    /** Returns the map that applies this one first and then the other one */
+    pub fn compose(&self, other: &Self) -> Self {
+        let mut pieces = vec![];
+
+        // Values moved by this map may be moved again by the other one
+        for &(src, offset) in &self.pieces {
+            for (part, other_offset) in other.split(src.shift(offset)) {
+                pieces.push((part.shift(-offset), offset + other_offset));
+            }
+        }
+
+        // Values left alone by this map are only affected by the other one
+        let sources: IntervalSet<T> = self.pieces.iter().map(|(src, _)| *src).collect();
+        for &(src, offset) in &other.pieces {
+            for &part in IntervalSet::from(src).difference(&sources).intervals() {
+                pieces.push((part, offset));
+            }
+        }
+
+        Self::from_pieces(pieces)
+    }
+
+    /** Returns the map that undoes this one, if it is a bijection. This
+        requires the destinations of the pieces to cover exactly the same
+        values as their sources, and to not overlap each other. */
+    pub fn invert(&self) -> Option<Self> {
+        let sources: IntervalSet<T> = self.pieces.iter().map(|(src, _)| *src).collect();
+        let dests: IntervalSet<T> = self.pieces.iter().map(|(src, offset)| src.shift(*offset)).collect();
+        let total = self.pieces.iter().fold(T::zero(), |acc, (src, _)| acc + src.len());
+
+        if sources != dests || dests.len() != total {
+            return None;
+        }
+
+        let pieces = self.pieces.iter().map(|&(src, offset)| (src.shift(offset), -offset)).collect();
+        Some(Self::from_pieces(pieces))
+    }
+
+    // Splits an interval into the parts covered by each piece and the parts
+    // not covered by any, paired with the offset that applies to each of them
+    fn split(&self, interval: Interval<T>) -> Vec<(Interval<T>, T)> {
+        let mut res = vec![];
+        let mut current = interval.start;
+        let first = self.pieces.partition_point(|(src, _)| src.end <= interval.start);
+
+        for &(src, offset) in &self.pieces[first..] {
+            if src.start >= interval.end {
+                break;
+            }
+
+            if src.start > current {
+                res.push((Interval::new(current, src.start), T::zero()));
+            }
+
+            let part = src.intersection(&interval);
+            res.push((part, offset));
+            current = part.end;
+        }
+
+        if current < interval.end {
+            res.push((Interval::new(current, interval.end), T::zero()));
+        }
+
+        res
+    }
+
+    // Builds a map from disjoint pieces, dropping those that do nothing
+    // and merging contiguous ones that have the same offset
+    fn from_pieces(mut pieces: Vec<(Interval<T>, T)>) -> Self {
+        pieces.retain(|(src, offset)| !src.is_empty() && !offset.is_zero());
+        pieces.sort_unstable();
+
+        let pieces = pieces.into_iter()
+            .coalesce(|(a, off_a), (b, off_b)| if a.end == b.start && off_a == off_b {
+                Ok((Interval::new(a.start, b.end), off_a))
+            } else {
+                Err(((a, off_a), (b, off_b)))
+            })
+            .collect();
+
+        Self { pieces }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect()
+    }
+
+    #[test]
+    fn difference_of_several_intervals() {
+        let whole = set(&[(0, 100)]);
+        let holes = set(&[(20, 30), (-10, 1), (5, 10), (90, 120)]);
+
+        assert_eq!(whole.difference(&holes), set(&[(1, 5), (10, 20), (30, 90)]));
+        assert_eq!(whole.difference(&holes).len(), 4 + 10 + 60);
+        assert!(whole.difference(&set(&[(-5, 40), (40, 100)])).is_empty());
+        assert_eq!(holes.difference(&IntervalSet::new()), holes);
+    }
+
+    #[test]
+    fn difference_across_several_intervals() {
+        let left = set(&[(0, 10), (20, 30), (40, 50)]);
+        let right = set(&[(5, 25), (45, 60)]);
+        assert_eq!(left.difference(&right), set(&[(0, 5), (25, 30), (40, 45)]));
+    }
+
+    #[test]
+    fn invert_round_trip() {
+        // The seed-to-soil map from the example of day 05
+        let mut map = RangeMap::new();
+        map.add_range(Interval::from_len(98, 2), 50);
+        map.add_range(Interval::from_len(50, 48), 52);
+
+        let inverse = map.invert().unwrap();
+        for val in -5..120 {
+            assert_eq!(inverse.apply(map.apply(val)), val);
+            assert_eq!(map.apply(inverse.apply(val)), val);
+        }
+        assert_eq!(map.compose(&inverse), RangeMap::new());
+    }
+
+    #[test]
+    fn invert_non_bijection() {
+        // Both 0..10 and 10..20 end up in 10..20
+        let mut map = RangeMap::new();
+        map.add_range(Interval::new(0, 10), 10);
+        assert_eq!(map.invert(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not overlap")]
+    fn overlap_with_identity_range() {
+        let mut map = RangeMap::new();
+        map.add_range(Interval::new(0, 10), 0);
+        map.add_range(Interval::new(5, 15), 100);
+    }
+
+    #[test]
+    fn identity_ranges_keep_values() {
+        let mut map = RangeMap::new();
+        map.add_range(Interval::new(0, 10), 0);
+        map.add_range(Interval::new(10, 20), 30);
+        assert_eq!(map.apply(5), 5);
+        assert_eq!(map.apply(15), 35);
+        assert_eq!(map.apply_set(&set(&[(8, 12)])), set(&[(8, 10), (30, 32)]));
+    }
+}
@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+
+use num::{BigInt, BigRational};
+use num::rational::Ratio;
+use num_traits::Num;
+
+/** An exact fraction of i128 values, fast but limited in range */
+pub type Rational = Ratio<i128>;
+
+/** Why a system of linear equations has no unique solution */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LinearSystemError {
+    /** The equations contradict each other, so there is no solution */
+    Inconsistent,
+    /** There are infinitely many solutions, since these variables can take any value */
+    Underdetermined { free_variables: Vec<usize> },
+}
+
+/** Solves the system A·x = b exactly using Gauss-Jordan elimination, where
+    every row of `matrix` holds the coefficients of one equation. Works with
+    any exact number type, such as `Rational` or `BigRational`. */
+// https://en.wikipedia.org/wiki/Gaussian_elimination
+pub fn solve_linear_system<T: Num + Clone>(matrix: &[Vec<T>], rhs: &[T]) -> Result<Vec<T>, LinearSystemError> {
+    assert_eq!(matrix.len(), rhs.len(), "There must be one right-hand side value per equation");
+    let vars = matrix.first().map_or(0, |row| row.len());
+    assert!(matrix.iter().all(|row| row.len() == vars), "All equations must have the same amount of coefficients");
+
+    // Augmented matrix, with the right-hand side as the last column
+    let mut rows = matrix.iter().zip(rhs)
+        .map(|(row, val)| row.iter().cloned().chain([val.clone()]).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let pivots = reduce(&mut rows, vars);
+
+    // A row of zeros equal to something that isn't zero means 0 = c
+    if rows[pivots.len()..].iter().any(|row| !row[vars].is_zero()) {
+        return Err(LinearSystemError::Inconsistent);
+    }
+
+    if pivots.len() < vars {
+        let free_variables = (0..vars).filter(|col| !pivots.contains(col)).collect();
+        return Err(LinearSystemError::Underdetermined { free_variables });
+    }
+
+    Ok(rows.into_iter().take(vars).map(|mut row| row.swap_remove(vars)).collect())
+}
+
+/** Same as `solve_linear_system`, but for integer coefficients. The solution
+    is computed with arbitrary precision, so intermediate values never overflow. */
+pub fn solve_integer_system(matrix: &[Vec<i128>], rhs: &[i128]) -> Result<Vec<BigRational>, LinearSystemError> {
+    let to_big = |val: &i128| BigRational::from_integer(BigInt::from(*val));
+    let matrix = matrix.iter().map(|row| row.iter().map(to_big).collect()).collect::<Vec<_>>();
+    let rhs = rhs.iter().map(to_big).collect::<Vec<_>>();
+    solve_linear_system(&matrix, &rhs)
+}
+
+// Transforms the augmented matrix into reduced row echelon form, returning
+// the column of the pivot of every non-zero row, which are moved to the top
+fn reduce<T: Num + Clone>(rows: &mut [Vec<T>], vars: usize) -> Vec<usize> {
+    let mut pivots = vec![];
+
+    for col in 0..vars {
+        let current = pivots.len();
+        let Some(found) = (current..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(current, found);
+
+        let pivot = rows[current][col].clone();
+        rows[current].iter_mut().for_each(|val| *val = val.clone() / pivot.clone());
+
+        let pivot_row = rows[current].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r == current || row[col].is_zero() {
+                continue;
+            }
+
+            let factor = row[col].clone();
+            for (val, pivot_val) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *val = val.clone() - factor.clone() * pivot_val.clone();
+            }
+        }
+
+        pivots.push(col);
+    }
+
+    pivots
+}
+
+impl Display for LinearSystemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinearSystemError::Inconsistent => write!(f, "The system has no solution"),
+            LinearSystemError::Underdetermined { free_variables } =>
+                write!(f, "The system has infinitely many solutions, with free variables {free_variables:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rationals(rows: &[&[i128]]) -> Vec<Vec<Rational>> {
+        rows.iter().map(|row| row.iter().map(|&x| Rational::from_integer(x)).collect()).collect()
+    }
+
+    #[test]
+    fn unique_solution() {
+        // 2x + y = 5, x - 3y = -1
+        let matrix = rationals(&[&[2, 1], &[1, -3]]);
+        let rhs = [Rational::from_integer(5), Rational::from_integer(-1)];
+        assert_eq!(solve_linear_system(&matrix, &rhs), Ok(vec![Rational::new(2, 1), Rational::new(1, 1)]));
+
+        // x + 2y = 1, 3x + 4y = 1
+        let solution = solve_integer_system(&[vec![1, 2], vec![3, 4]], &[1, 1]).unwrap();
+        assert_eq!(solution, vec![BigRational::from_integer((-1).into()), BigRational::from_integer(1.into())]);
+    }
+
+    #[test]
+    fn inconsistent_system() {
+        // x + 2y = 3, 2x + 4y = 7
+        let res = solve_integer_system(&[vec![1, 2], vec![2, 4]], &[3, 7]);
+        assert_eq!(res, Err(LinearSystemError::Inconsistent));
+    }
+
+    #[test]
+    fn underdetermined_system() {
+        // x + 2y = 3, 2x + 4y = 6
+        let res = solve_integer_system(&[vec![1, 2], vec![2, 4]], &[3, 6]);
+        assert_eq!(res, Err(LinearSystemError::Underdetermined { free_variables: vec![1] }));
+    }
+}
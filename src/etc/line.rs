@@ -0,0 +1,403 @@
+#![allow(dead_code)]
+
+use num::rational::Ratio;
+use num_traits::{Num, PrimInt, Signed};
+
+use super::coords::Coords2D;
+
+type Point = Coords2D<i64>;
+
+// Intersections are computed exactly with i128 as long as every coordinate
+// is strictly within +-2^62 and both |p| * |d|^2 and |d|^3 stay below this limit,
+// where p and d are the largest origin and direction coordinates involved
+const RANGE_LIMIT: i128 = 1 << 120;
+
+/** A point with exact rational coordinates */
+pub type RationalPoint = Coords2D<Ratio<i128>>;
+
+/** A line segment between two integer points, both included */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point,
+    pub end: Point,
+}
+
+/** A half-line that starts at an integer point and
+    goes on forever in the given direction */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ray {
+    pub origin: Point,
+    pub direction: Point,
+}
+
+/** The result of intersecting two segments or rays */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Intersection {
+    None,
+    Point(RationalPoint),
+    /** Both are colinear and share more than a single point */
+    Overlap,
+}
+
+/** Iterates over the grid cells that approximate a straight line
+    between two points, both included, using Bresenham's algorithm */
+#[derive(Clone, Debug)]
+pub struct LineCells<T: Num> {
+    current: Coords2D<T>,
+    end: Coords2D<T>,
+    dx: T,
+    dy: T,
+    step: Coords2D<T>,
+    error: T,
+    done: bool,
+}
+
+// Describes a set of points o + t*d, where t >= 0, and also t <= 1 if bounded
+#[derive(Copy, Clone, Debug)]
+struct Parametric {
+    origin: Point,
+    direction: Point,
+    bounded: bool,
+}
+
+impl Segment {
+    pub fn new(start: Point, end: Point) -> Self {
+        Self { start, end }
+    }
+
+    pub fn direction(&self) -> Point {
+        self.end - self.start
+    }
+
+    /** The top-left and bottom-right corners of the smallest
+        axis-aligned rectangle that contains the segment */
+    pub fn bounding_box(&self) -> (Point, Point) {
+        Point::bounding_box([self.start, self.end]).unwrap()
+    }
+
+    pub fn is_parallel(&self, other: &Self) -> bool {
+        cross(self.direction(), other.direction()) == 0
+    }
+
+    /** Whether both segments lie on the same infinite line */
+    pub fn is_colinear(&self, other: &Self) -> bool {
+        self.is_parallel(other) && cross(self.direction(), other.start - self.start) == 0
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        let (min, max) = self.bounding_box();
+        cross(self.direction(), point - self.start) == 0
+            && (min.x..=max.x).contains(&point.x)
+            && (min.y..=max.y).contains(&point.y)
+    }
+
+    /** The grid cells along the segment, see `Coords2D::line_to` */
+    pub fn cells(&self) -> LineCells<i64> {
+        self.start.line_to(self.end)
+    }
+
+    /** Finds the exact point where two segments cross. Panics if either
+        of them has the same start and end point, or if the coordinates are
+        too large to compute it exactly: they must be strictly within +-2^62, and the
+        length of the segments cubed or times the coordinates squared must
+        stay below 2^120. */
+    pub fn intersection(&self, other: &Self) -> Intersection {
+        self.parametric().intersection(&other.parametric())
+    }
+
+    pub fn intersection_with_ray(&self, ray: &Ray) -> Intersection {
+        self.parametric().intersection(&ray.parametric())
+    }
+
+    fn parametric(&self) -> Parametric {
+        Parametric {
+            origin: self.start,
+            direction: self.direction(),
+            bounded: true,
+        }
+    }
+}
+
+impl Ray {
+    pub fn new(origin: Point, direction: Point) -> Self {
+        Self { origin, direction }
+    }
+
+    pub fn is_parallel(&self, other: &Self) -> bool {
+        cross(self.direction, other.direction) == 0
+    }
+
+    /** Whether both rays lie on the same infinite line */
+    pub fn is_colinear(&self, other: &Self) -> bool {
+        self.is_parallel(other) && cross(self.direction, other.origin - self.origin) == 0
+    }
+
+    /** The position after moving `t` times along the direction */
+    pub fn point_at(&self, t: Ratio<i128>) -> RationalPoint {
+        self.parametric().point_at(t)
+    }
+
+    /** Finds the exact point where two rays cross. Panics if either of them
+        has a direction of (0, 0), or if the coordinates are too large, with
+        the same limits as `Segment::intersection`. */
+    pub fn intersection(&self, other: &Self) -> Intersection {
+        self.parametric().intersection(&other.parametric())
+    }
+
+    pub fn intersection_with_segment(&self, segment: &Segment) -> Intersection {
+        self.parametric().intersection(&segment.parametric())
+    }
+
+    fn parametric(&self) -> Parametric {
+        Parametric {
+            origin: self.origin,
+            direction: self.direction,
+            bounded: false,
+        }
+    }
+}
+
+impl Parametric {
+    fn point_at(&self, t: Ratio<i128>) -> RationalPoint {
+        self.point_at_fraction(*t.numer(), *t.denom())
+    }
+
+    // Same as point_at(num / denom) but only reducing the final coordinates
+    fn point_at_fraction(&self, num: i128, denom: i128) -> RationalPoint {
+        let coord = |o: i64, d: i64| Ratio::new(o as i128 * denom + num * d as i128, denom);
+        RationalPoint::new(coord(self.origin.x, self.direction.x), coord(self.origin.y, self.direction.y))
+    }
+
+    // Checks whether num / denom is a valid value of t, with denom > 0
+    fn contains_fraction(&self, num: i128, denom: i128) -> bool {
+        num >= 0 && (!self.bounded || num <= denom)
+    }
+
+    // The largest absolute value of any origin and any direction coordinate
+    fn magnitudes(&self) -> (i128, i128) {
+        let largest = |p: Point| (p.x as i128).abs().max((p.y as i128).abs());
+        (largest(self.origin), largest(self.direction))
+    }
+
+    fn intersection(&self, other: &Self) -> Intersection {
+        assert!(self.direction != Point::origin() && other.direction != Point::origin(),
+            "The direction of a segment or ray must not be zero");
+
+        let (p1, d1) = self.magnitudes();
+        let (p2, d2) = other.magnitudes();
+        let (p, d) = (p1.max(p2), d1.max(d2));
+        let within_limit = |val: Option<i128>| val.is_some_and(|val| val < RANGE_LIMIT);
+        assert!(p < 1 << 62 && within_limit((d * d).checked_mul(p)) && within_limit((d * d).checked_mul(d)),
+            "Coordinates too large to intersect exactly");
+
+        let denom = cross(self.direction, other.direction);
+        let offset = other.origin - self.origin;
+
+        if denom != 0 {
+            // Solve self.origin + t * self.dir = other.origin + s * other.dir
+            let sign = denom.signum();
+            let (t, s) = (cross(offset, other.direction) * sign, cross(offset, self.direction) * sign);
+
+            return if self.contains_fraction(t, denom.abs()) && other.contains_fraction(s, denom.abs()) {
+                Intersection::Point(self.point_at_fraction(t, denom.abs()))
+            } else {
+                Intersection::None
+            };
+        }
+
+        if cross(self.direction, offset) != 0 {
+            return Intersection::None;
+        }
+
+        // Both are colinear, so express the range of the other one in terms
+        // of the parameter of this one, multiplied by the squared length of
+        // this direction so that every value is an integer
+        let len_sq = dot(self.direction, self.direction);
+        let start = dot(offset, self.direction);
+        let scale = dot(other.direction, self.direction);
+
+        let (mut min, mut max) = (Some(start), other.bounded.then_some(start + scale));
+        if scale < 0 {
+            (min, max) = (max, min);
+        }
+
+        let min = [min, Some(0)].into_iter().flatten().max();
+        let max = [max, self.bounded.then_some(len_sq)].into_iter().flatten().min();
+
+        match (min, max) {
+            (Some(min), Some(max)) if min > max => Intersection::None,
+            (Some(min), Some(max)) if min == max => Intersection::Point(self.point_at_fraction(min, len_sq)),
+            _ => Intersection::Overlap,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Coords2D<T> {
+    /** Iterates over the grid cells along a straight line from this
+        point to another one, both included */
+    pub fn line_to(self, end: Self) -> LineCells<T> {
+        let dx = (end.x - self.x).abs();
+        let dy = -(end.y - self.y).abs();
+        let step = Self::new((end.x - self.x).signum(), (end.y - self.y).signum());
+        LineCells { current: self, end, dx, dy, step, error: dx + dy, done: false }
+    }
+}
+
+impl<T: Num + Copy + PartialOrd> Coords2D<T> {
+    /** The top-left and bottom-right corners of the smallest axis-aligned
+        rectangle that contains all the points, or None if there are none */
+    pub fn bounding_box<P: IntoIterator<Item = Self>>(points: P) -> Option<(Self, Self)> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+
+        let smaller = |a: T, b: T| if b < a { b } else { a };
+        let larger = |a: T, b: T| if b > a { b } else { a };
+
+        Some(points.fold((first, first), |(min, max), p| {
+            (Self::new(smaller(min.x, p.x), smaller(min.y, p.y)),
+             Self::new(larger(max.x, p.x), larger(max.y, p.y)))
+        }))
+    }
+}
+
+impl<T: PrimInt + Signed> Iterator for LineCells<T> {
+    type Item = Coords2D<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let res = self.current;
+        if self.current == self.end {
+            self.done = true;
+            return Some(res);
+        }
+
+        let doubled = self.error + self.error;
+        if doubled >= self.dy {
+            self.error = self.error + self.dy;
+            self.current.x = self.current.x + self.step.x;
+        }
+        if doubled <= self.dx {
+            self.error = self.error + self.dx;
+            self.current.y = self.current.y + self.step.y;
+        }
+
+        Some(res)
+    }
+}
+
+fn cross(a: Point, b: Point) -> i128 {
+    a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128
+}
+
+fn dot(a: Point, b: Point) -> i128 {
+    a.x as i128 * b.x as i128 + a.y as i128 * b.y as i128
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seg(start: (i64, i64), end: (i64, i64)) -> Segment {
+        Segment::new(start.into(), end.into())
+    }
+
+    fn point(x: (i128, i128), y: (i128, i128)) -> Intersection {
+        Intersection::Point(RationalPoint::new(Ratio::new(x.0, x.1), Ratio::new(y.0, y.1)))
+    }
+
+    #[test]
+    fn crossing_segments() {
+        assert_eq!(seg((0, 0), (4, 4)).intersection(&seg((0, 4), (4, 0))), point((2, 1), (2, 1)));
+        assert_eq!(seg((0, 0), (3, 1)).intersection(&seg((0, 1), (1, 0))), point((3, 4), (1, 4)));
+        assert_eq!(seg((0, 0), (1, 1)).intersection(&seg((0, 4), (4, 0))), Intersection::None);
+    }
+
+    #[test]
+    fn touching_endpoints() {
+        // At an angle, and colinear but pointing in opposite directions
+        assert_eq!(seg((0, 0), (2, 0)).intersection(&seg((2, 0), (2, 5))), point((2, 1), (0, 1)));
+        assert_eq!(seg((0, 0), (2, 2)).intersection(&seg((4, 4), (2, 2))), point((2, 1), (2, 1)));
+        assert_eq!(seg((2, 2), (0, 0)).intersection(&seg((2, 2), (4, 4))), point((2, 1), (2, 1)));
+    }
+
+    #[test]
+    fn colinear_segments() {
+        assert_eq!(seg((0, 0), (4, 2)).intersection(&seg((2, 1), (8, 4))), Intersection::Overlap);
+        assert_eq!(seg((0, 0), (4, 2)).intersection(&seg((8, 4), (-2, -1))), Intersection::Overlap);
+        assert_eq!(seg((0, 0), (4, 2)).intersection(&seg((6, 3), (8, 4))), Intersection::None);
+        assert!(seg((0, 0), (4, 2)).is_colinear(&seg((6, 3), (8, 4))));
+    }
+
+    #[test]
+    fn parallel_disjoint_segments() {
+        let (a, b) = (seg((0, 0), (4, 2)), seg((0, 1), (4, 3)));
+        assert!(a.is_parallel(&b));
+        assert!(!a.is_colinear(&b));
+        assert_eq!(a.intersection(&b), Intersection::None);
+    }
+
+    #[test]
+    fn rays_and_segments() {
+        let ray = Ray::new((0, 0).into(), (1, 0).into());
+        assert_eq!(ray.intersection_with_segment(&seg((5, -1), (5, 1))), point((5, 1), (0, 1)));
+        assert_eq!(ray.intersection_with_segment(&seg((-5, -1), (-5, 1))), Intersection::None);
+        assert_eq!(ray.intersection_with_segment(&seg((-3, 0), (-1, 0))), Intersection::None);
+        assert_eq!(ray.intersection_with_segment(&seg((-3, 0), (0, 0))), point((0, 1), (0, 1)));
+        assert_eq!(ray.intersection(&Ray::new((9, 0).into(), (-1, 0).into())), Intersection::Overlap);
+        assert_eq!(ray.intersection(&Ray::new((0, 0).into(), (-1, 0).into())), point((0, 1), (0, 1)));
+    }
+
+    #[test]
+    fn large_coordinates() {
+        let big = 1 << 48;
+        let a = Ray::new((big, 0).into(), (-1000, 1000).into());
+        let b = Ray::new((0, 0).into(), (999, 1001).into());
+        let Intersection::Point(p) = a.intersection(&b) else { panic!("The rays should cross") };
+        assert_eq!(p.x + p.y, Ratio::from_integer(big as i128));
+    }
+
+    #[test]
+    #[should_panic(expected = "Coordinates too large")]
+    fn coordinates_out_of_range() {
+        let big = 1 << 50;
+        Ray::new((0, 0).into(), (big, 1).into()).intersection(&Ray::new((1, 0).into(), (1, big).into()));
+    }
+
+    #[test]
+    fn segment_contains_and_bounding_box() {
+        let s = seg((4, -2), (-2, 1));
+        assert_eq!(s.bounding_box(), ((-2, -2).into(), (4, 1).into()));
+        assert!(s.contains((0, 0).into()));
+        assert!(s.contains((4, -2).into()));
+        assert!(s.contains((-2, 1).into()));
+        assert!(!s.contains((6, -3).into()));
+        assert!(!s.contains((1, 0).into()));
+    }
+
+    #[test]
+    fn line_cells_in_every_octant() {
+        let targets = [(5, 2), (2, 5), (-2, 5), (-5, 2), (-5, -2), (-2, -5), (2, -5), (5, -2),
+                       (4, 0), (0, 4), (-4, 0), (0, -4), (3, 3), (-3, 3), (0, 0)];
+
+        for (x, y) in targets {
+            let end = Point::new(x, y);
+            let cells: Vec<Point> = Point::origin().line_to(end).collect();
+
+            assert_eq!(cells.first(), Some(&Point::origin()));
+            assert_eq!(cells.last(), Some(&end));
+            assert_eq!(cells.len() as i64, x.abs().max(y.abs()) + 1, "line to {end:?}");
+
+            // Every step moves to an adjacent cell, and every
+            // cell is at most half a cell away from the line
+            for (a, b) in cells.iter().zip(&cells[1..]) {
+                assert!((b.x - a.x).abs() <= 1 && (b.y - a.y).abs() <= 1);
+            }
+            for cell in &cells {
+                assert!(2 * cross(end, *cell).abs() <= x.abs().max(y.abs()) as i128, "{cell:?} on line to {end:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,194 @@
+#![allow(dead_code)]
+
+use itertools::Itertools;
+use num_traits::{PrimInt, Signed};
+
+use super::cycle::Cycle;
+
+/** Extended Euclidean algorithm. Returns (g, x, y) such that
+    g = gcd(a, b) >= 0 and a*x + b*y = g */
+pub fn ext_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+
+    while !r.is_zero() {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/** The value x in [0, m) such that a*x = 1 (mod m), if it exists */
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = ext_gcd(a, m);
+    if g.is_one() {
+        Some(modulo(x, m))
+    } else {
+        None
+    }
+}
+
+/** The remainder of a / m in [0, |m|), even for negative values */
+pub fn modulo<T: PrimInt + Signed>(a: T, m: T) -> T {
+    let m = m.abs();
+    let r = a % m;
+    if r < T::zero() { r + m } else { r }
+}
+
+/** Solves a system of congruences x = r (mod m) given as (r, m) pairs,
+    where the moduli do not need to be pairwise coprime. Returns the
+    smallest non-negative solution and the modulus of all solutions
+    (the LCM of the moduli), or None if there is no solution. Intermediate
+    values can reach the square of the moduli, so use i128 for big ones. */
+// https://en.wikipedia.org/wiki/Chinese_remainder_theorem#Generalization_to_non-coprime_moduli
+pub fn crt<T: PrimInt + Signed>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut res = (T::zero(), T::one());
+
+    for &(r2, m2) in congruences {
+        assert!(m2 > T::zero(), "Moduli must be positive");
+        let (r1, m1) = res;
+        let (g, p, _) = ext_gcd(m1, m2);
+
+        let diff = r2 - r1;
+        if !(diff % g).is_zero() {
+            return None;
+        }
+
+        // x = r1 + m1 * k, where k = (diff / g) * p (mod m2 / g)
+        let step = m2 / g;
+        let k = modulo(modulo(diff / g, step) * modulo(p, step), step);
+        let lcm = m1 * step;
+        res = (modulo(r1 + m1 * k, lcm), lcm);
+    }
+
+    Some(res)
+}
+
+/** Finds the first time at which a set of periodic signals all happen at
+    once. Every signal is given as (first, period), meaning that it happens
+    at times first, first + period, first + 2*period... Unlike the LCM of
+    the periods, this also works when the signals start at different times. */
+pub fn first_alignment<T: PrimInt + Signed>(signals: &[(T, T)]) -> Option<T> {
+    let congruences = signals.iter().map(|&(first, period)| (modulo(first, period), period)).collect::<Vec<_>>();
+    let (x, lcm) = crt(&congruences)?;
+
+    // Every signal must have started, so skip ahead to the first valid time
+    let start = signals.iter().map(|&(first, _)| first).max().unwrap_or(T::zero());
+    if x >= start {
+        Some(x)
+    } else {
+        let cycles = (start - x + lcm - T::one()) / lcm;
+        Some(x + cycles * lcm)
+    }
+}
+
+/** Finds the first time at which several cyclic signals all happen at once.
+    Every signal is given as the cycle of the states that produce it, along
+    with the steps at which it happens up to the end of the first repetition
+    of that cycle. Those before the cycle starts only happen once, while the
+    ones inside it repeat with its period, so every signal may happen several
+    times per period and the cycles may start at different times. */
+pub fn first_common_event<T: PrimInt + Signed>(signals: &[(Cycle, &[T])]) -> Option<T> {
+    let to_t = |val: usize| T::from(val).unwrap();
+    let happens_at = |&(cycle, events): &(Cycle, &[T]), time: T| {
+        events.contains(&to_t(cycle.equivalent_step(time.to_usize().unwrap())))
+    };
+
+    // Events before a cycle starts can only align at that very same time
+    let one_off = signals.iter()
+        .flat_map(|(cycle, events)| events.iter().copied().filter(|&time| time < to_t(cycle.offset)))
+        .filter(|&time| signals.iter().all(|signal| happens_at(signal, time)));
+
+    // Any combination of one event per signal inside the loops may align
+    let periodic = signals.iter()
+        .map(|(cycle, events)| events.iter()
+            .filter(|&&time| time >= to_t(cycle.offset) && time < to_t(cycle.offset + cycle.period))
+            .map(|&time| (time, to_t(cycle.period)))
+            .collect_vec())
+        .multi_cartesian_product()
+        .filter_map(|combination| first_alignment(&combination));
+
+    one_off.chain(periodic).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modulo_of_negative_values() {
+        assert_eq!(modulo(-7, 3), 2);
+        assert_eq!(modulo(-6, 3), 0);
+        assert_eq!(modulo(7, -3), 1);
+        assert_eq!(modulo(-7i64, -3), 2);
+    }
+
+    #[test]
+    fn inverses() {
+        assert_eq!(mod_inverse(3, 7), Some(5));
+        assert_eq!(mod_inverse(-3, 7), Some(2));
+        assert_eq!(mod_inverse(2, 4), None);
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn crt_with_non_coprime_moduli() {
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(3, 6), (3, 10), (3, 15)]), Some((3, 30)));
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn crt_without_solution() {
+        // x would have to be both odd and even
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(0, 10), (1, 5), (0, 3)]), None);
+    }
+
+    #[test]
+    fn alignment_waits_for_every_signal() {
+        assert_eq!(first_alignment(&[(3, 4), (5, 6)]), Some(11));
+        assert_eq!(first_alignment(&[(2, 3), (14, 7)]), Some(14));
+        assert_eq!(first_alignment(&[(8, 4), (2, 2)]), Some(8));
+        assert_eq!(first_alignment(&[(1, 2), (2, 4)]), None);
+    }
+
+    #[test]
+    fn common_event_inside_the_loops() {
+        let a = (Cycle { offset: 2, period: 4 }, &[3, 5][..]);
+        let b = (Cycle { offset: 0, period: 6 }, &[1][..]);
+        // a happens at 3, 5, 7, 9, 11... and b at 1, 7, 13...
+        assert_eq!(first_common_event(&[a, b]), Some(7));
+        assert_eq!(first_common_event(&[a]), Some(3));
+    }
+
+    #[test]
+    fn common_event_before_a_loop() {
+        // b only happens once at 1, before its loop starts at 4
+        let a = (Cycle { offset: 0, period: 1 }, &[0][..]);
+        let b = (Cycle { offset: 4, period: 3 }, &[1, 5][..]);
+        assert_eq!(first_common_event(&[a, b]), Some(1));
+
+        // And b happens outside of its loop when a does not
+        let a = (Cycle { offset: 0, period: 2 }, &[0][..]);
+        assert_eq!(first_common_event(&[a, b]), Some(8));
+    }
+
+    #[test]
+    fn common_event_never_happens() {
+        let a = (Cycle { offset: 0, period: 2 }, &[0][..]);
+        let b = (Cycle { offset: 0, period: 4 }, &[1, 3][..]);
+        let c = (Cycle { offset: 0, period: 3 }, &[][..]);
+        assert_eq!(first_common_event(&[a, b]), None);
+        assert_eq!(first_common_event(&[a, c]), None);
+    }
+}
@@ -0,0 +1,217 @@
+#![allow(dead_code)]
+
+use std::hash::Hash;
+
+use rustc_hash::FxHashMap;
+
+/** Storage for the results of a memoized function */
+pub trait MemoTable<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, val: V);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq, V> MemoTable<K, V> for FxHashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        FxHashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, val: V) {
+        FxHashMap::insert(self, key, val);
+    }
+
+    fn len(&self) -> usize {
+        FxHashMap::len(self)
+    }
+}
+
+/** A table for keys made of N small indices, each one lower than the
+    corresponding dimension, stored in a flat vector in row-major order.
+    Much faster than hashing when most of the possible keys are used. */
+#[derive(Clone, Debug)]
+pub struct DenseTable<V, const N: usize> {
+    dims: [usize; N],
+    data: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V, const N: usize> DenseTable<V, N> {
+    pub fn new(dims: [usize; N]) -> Self {
+        let size = dims.iter().product();
+        Self { dims, data: (0..size).map(|_| None).collect(), len: 0 }
+    }
+
+    fn flat_index(&self, key: &[usize; N]) -> usize {
+        key.iter().zip(&self.dims).fold(0, |acc, (&i, &dim)| {
+            assert!(i < dim, "Index {i} out of bounds for a dimension of size {dim}");
+            acc * dim + i
+        })
+    }
+}
+
+impl<V, const N: usize> MemoTable<[usize; N], V> for DenseTable<V, N> {
+    fn get(&self, key: &[usize; N]) -> Option<&V> {
+        self.data[self.flat_index(key)].as_ref()
+    }
+
+    fn insert(&mut self, key: [usize; N], val: V) {
+        let ix = self.flat_index(&key);
+        if self.data[ix].replace(val).is_none() {
+            self.len += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/** How often a memoized function found its result already computed */
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+impl MemoStats {
+    pub fn calls(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    /** The fraction of calls that were answered from the table, between 0 and 1 */
+    pub fn hit_rate(&self) -> f64 {
+        if self.calls() == 0 { 0.0 } else { self.hits as f64 / self.calls() as f64 }
+    }
+}
+
+/** Remembers the results of a recursive function. The function is written
+    as a closure that receives the memo itself, so that it can use it for
+    its recursive calls. Keys may borrow data, such as slices of the input. */
+#[derive(Clone, Debug)]
+pub struct Memo<K, V, T: MemoTable<K, V>> {
+    table: T,
+    stats: MemoStats,
+    _types: std::marker::PhantomData<(K, V)>,
+}
+
+/** A memo that stores its results in a hash map, valid for any key */
+pub type HashMemo<K, V> = Memo<K, V, FxHashMap<K, V>>;
+
+/** A memo whose keys are N small indices, see `DenseTable` */
+pub type DenseMemo<V, const N: usize> = Memo<[usize; N], V, DenseTable<V, N>>;
+
+impl<K: Hash + Eq, V: Clone> HashMemo<K, V> {
+    pub fn new() -> Self {
+        Self::with_table(FxHashMap::default())
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for HashMemo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Clone, const N: usize> DenseMemo<V, N> {
+    /** Creates a memo for keys whose i-th index is always lower than `dims[i]` */
+    pub fn dense(dims: [usize; N]) -> Self {
+        Self::with_table(DenseTable::new(dims))
+    }
+}
+
+impl<K, V: Clone, T: MemoTable<K, V>> Memo<K, V, T> {
+    pub fn with_table(table: T) -> Self {
+        Self { table, stats: MemoStats::default(), _types: std::marker::PhantomData }
+    }
+
+    /** Returns the stored result for a key, or computes it and stores it */
+    pub fn get_or_compute<F>(&mut self, key: K, compute: F) -> V
+    where F: FnOnce(&mut Self) -> V
+    {
+        if let Some(val) = self.table.get(&key) {
+            self.stats.hits += 1;
+            return val.clone();
+        }
+
+        self.stats.misses += 1;
+        let val = compute(self);
+        self.table.insert(key, val.clone());
+        val
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.table.get(key)
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /** The amount of results stored */
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ways to split a slice into pieces of one or two elements, which
+    // follows the Fibonacci sequence, memoized by the borrowed suffix
+    fn splits<'a>(data: &'a [u8], memo: &mut HashMemo<&'a [u8], u64>) -> u64 {
+        memo.get_or_compute(data, |memo| {
+            if data.len() <= 1 { 1 } else { splits(&data[1..], memo) + splits(&data[2..], memo) }
+        })
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let data = vec![0; 30];
+        let mut memo = HashMemo::new();
+        assert_eq!(splits(&data, &mut memo), 1_346_269);
+
+        // Every suffix is computed once and then reused by the one before it
+        assert_eq!(memo.len(), 31);
+        assert_eq!(memo.stats(), MemoStats { hits: 28, misses: 31 });
+        assert_eq!(memo.get(&&data[25..]), Some(&8));
+        assert_eq!(memo.get(&&[1u8][..]), None);
+    }
+
+    #[test]
+    fn hit_rates() {
+        assert_eq!(MemoStats::default().hit_rate(), 0.0);
+        assert_eq!(MemoStats { hits: 3, misses: 1 }.hit_rate(), 0.75);
+        assert_eq!(MemoStats { hits: 0, misses: 5 }.hit_rate(), 0.0);
+
+        let mut memo: HashMemo<u32, u32> = HashMemo::new();
+        memo.get_or_compute(1, |_| 10);
+        assert_eq!(memo.get_or_compute(1, |_| unreachable!()), 10);
+        assert_eq!(memo.stats().hit_rate(), 0.5);
+    }
+
+    #[test]
+    fn dense_keys() {
+        let mut memo = DenseMemo::dense([3, 4]);
+        assert_eq!(memo.get_or_compute([2, 3], |_| 'x'), 'x');
+        assert_eq!(memo.get_or_compute([2, 3], |_| 'y'), 'x');
+        assert_eq!(memo.get(&[0, 0]), None);
+        assert_eq!(memo.len(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn dense_key_out_of_bounds() {
+        // The flat index would still be in range, but the second index is not
+        let mut memo = DenseMemo::dense([3, 4]);
+        memo.get_or_compute([0, 4], |_| 0);
+    }
+}
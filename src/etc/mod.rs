@@ -7,8 +7,60 @@ pub use consts::DOUBLE_NEWLINE;
 pub mod vecmat;
 pub use vecmat::VecMat;
 
+pub mod subgrid;
+
+pub mod grid_bfs;
+
+pub mod grid_graph;
+
+pub mod graph;
+pub use graph::LabeledGraph;
+
+pub mod search;
+pub use search::SearchProblem;
+
+pub mod tiled_grid;
+pub use tiled_grid::TiledGrid;
+
+pub mod sparse_grid;
+
+pub mod bitgrid;
+pub use bitgrid::BitGrid;
+
+pub mod automaton;
+pub use automaton::Automaton;
+
+pub mod render;
+
+pub mod image;
+
+pub mod animation;
+
 pub mod coords;
-pub use coords::Coords2D;
+pub use coords::{Axis, Coords2D, Coords3D, Direction};
+
+pub mod hex;
+
+pub mod line;
+pub use line::{Intersection, Ray};
+
+pub mod interval;
+pub use interval::{Interval, IntervalSet, RangeMap};
+
+pub mod cycle;
+pub use cycle::Cycle;
+
+pub mod memo;
+pub use memo::DenseMemo;
+
+pub mod math;
+
+pub mod polynomial;
+
+pub mod linalg;
+
+pub mod polygon;
+pub use polygon::Polygon;
 
 pub mod id_assigner;
-pub use id_assigner::IDAssigner;
\ No newline at end of file
+pub use id_assigner::StrInterner;
\ No newline at end of file
@@ -0,0 +1,233 @@
+#![allow(dead_code)]
+
+use itertools::Itertools;
+use num::integer::gcd;
+
+use super::coords::{Coords2D, Direction};
+
+type Point = Coords2D<i64>;
+
+/** A simple polygon with integer vertices. Coordinates are stored as i64,
+    and all the intermediate computations are done with i128 so that
+    they do not overflow even with huge coordinates. */
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+/** The direction in which the vertices of a polygon are listed, assuming
+    that the y axis grows downwards like in a grid */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /** All the vertices are colinear, so there is no enclosed area */
+    Degenerate,
+}
+
+/** Where a point lies with respect to a polygon */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PointLocation {
+    Inside,
+    Outside,
+    OnBoundary,
+}
+
+impl Polygon {
+    /** Creates a polygon from its vertices in order. The polygon is
+        closed implicitly, but the first vertex may also be repeated
+        at the end. */
+    pub fn new(mut vertices: Vec<Point>) -> Self {
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    /** Builds a polygon by following a list of moves from a starting point,
+        where every move goes a number of steps in a direction. The moves
+        are expected to end back at the starting point. */
+    pub fn from_moves<M: IntoIterator<Item = (Direction, i64)>>(start: Point, moves: M) -> Self {
+        let mut current = start;
+        let mut vertices = vec![current];
+
+        for (dir, amount) in moves {
+            current += dir.to_coords::<i64>() * amount;
+            vertices.push(current);
+        }
+
+        Self::new(vertices)
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    /** Iterates over the edges as pairs of consecutive vertices,
+        including the one that closes the polygon */
+    pub fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices.iter().copied().circular_tuple_windows()
+    }
+
+    /** Twice the signed area, which is always an integer. It is positive
+        when the vertices are listed clockwise (with y growing downwards). */
+    // https://en.wikipedia.org/wiki/Shoelace_formula
+    pub fn signed_double_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| a.x as i128 * b.y as i128 - a.y as i128 * b.x as i128)
+            .sum()
+    }
+
+    /** Twice the enclosed area, which is always an integer */
+    pub fn double_area(&self) -> i128 {
+        self.signed_double_area().abs()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.double_area() as f64 / 2.
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        match self.signed_double_area().signum() {
+            1 => Orientation::Clockwise,
+            -1 => Orientation::CounterClockwise,
+            _ => Orientation::Degenerate,
+        }
+    }
+
+    /** Total length of the edges */
+    pub fn perimeter(&self) -> f64 {
+        self.edges()
+            .map(|(a, b)| {
+                let (dx, dy) = ((b.x - a.x) as f64, (b.y - a.y) as f64);
+                dx.hypot(dy)
+            })
+            .sum()
+    }
+
+    /** Amount of integer points that lie on the edges. For polygons with
+        only horizontal and vertical edges, this is the same as the perimeter. */
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| gcd(b.x as i128 - a.x as i128, b.y as i128 - a.y as i128))
+            .sum()
+    }
+
+    /** Amount of integer points strictly inside the polygon */
+    // https://en.wikipedia.org/wiki/Pick's_theorem
+    pub fn interior_points(&self) -> i128 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /** Amount of integer points inside the polygon or on its edges */
+    pub fn lattice_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    /** Checks whether a point is inside, outside or on the edges of the polygon */
+    pub fn locate(&self, point: Point) -> PointLocation {
+        let (px, py) = (point.x as i128, point.y as i128);
+        let mut inside = false;
+
+        for (a, b) in self.edges() {
+            let (ax, ay, bx, by) = (a.x as i128, a.y as i128, b.x as i128, b.y as i128);
+
+            let cross = (bx - ax) * (py - ay) - (by - ay) * (px - ax);
+            let within_box = px >= ax.min(bx) && px <= ax.max(bx) && py >= ay.min(by) && py <= ay.max(by);
+            if cross == 0 && within_box {
+                return PointLocation::OnBoundary;
+            }
+
+            // Cast a ray towards +x and count the edges it crosses,
+            // treating every edge as half-open in the y axis
+            if (ay > py) != (by > py) {
+                let crosses_right = if by > ay { cross > 0 } else { cross < 0 };
+                if crosses_right {
+                    inside = !inside;
+                }
+            }
+        }
+
+        if inside { PointLocation::Inside } else { PointLocation::Outside }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.locate(point) != PointLocation::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn polygon(points: &[(i64, i64)]) -> Polygon {
+        Polygon::new(points.iter().map(|&p| p.into()).collect())
+    }
+
+    // A 6x6 square with a 2x4 notch cut from the middle of its bottom edge,
+    // listed clockwise with y growing downwards
+    fn notched() -> Polygon {
+        polygon(&[(0, 0), (6, 0), (6, 6), (4, 6), (4, 2), (2, 2), (2, 6), (0, 6)])
+    }
+
+    fn reversed(poly: &Polygon) -> Polygon {
+        Polygon::new(poly.vertices().iter().rev().copied().collect())
+    }
+
+    #[test]
+    fn locate_in_concave_polygon() {
+        for poly in [notched(), reversed(&notched())] {
+            for inside in [(1, 1), (5, 4), (3, 1), (1, 5)] {
+                assert_eq!(poly.locate(inside.into()), PointLocation::Inside, "{inside:?}");
+            }
+            for outside in [(3, 4), (3, 6), (7, 0), (-1, 3), (3, -1), (3, 7)] {
+                assert_eq!(poly.locate(outside.into()), PointLocation::Outside, "{outside:?}");
+            }
+            for edge in [(3, 2), (6, 3), (4, 4), (1, 6)] {
+                assert_eq!(poly.locate(edge.into()), PointLocation::OnBoundary, "{edge:?}");
+            }
+            for vertex in [(0, 0), (4, 6), (2, 2)] {
+                assert_eq!(poly.locate(vertex.into()), PointLocation::OnBoundary, "{vertex:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn winding_does_not_change_counts() {
+        let cw = notched();
+        let ccw = reversed(&cw);
+
+        assert_eq!(cw.orientation(), Orientation::Clockwise);
+        assert_eq!(ccw.orientation(), Orientation::CounterClockwise);
+        assert_eq!(cw.signed_double_area(), 56);
+        assert_eq!(ccw.signed_double_area(), -56);
+
+        for poly in [cw, ccw] {
+            assert_eq!(poly.area(), 28.);
+            assert_eq!(poly.boundary_points(), 32);
+            assert_eq!(poly.interior_points(), 13);
+            assert_eq!(poly.lattice_points(), 45);
+        }
+    }
+
+    #[test]
+    fn diagonal_edges() {
+        // Inside points are (1, 1), (1, 2) and (2, 1)
+        let triangle = polygon(&[(0, 0), (0, 4), (4, 0), (0, 0)]);
+        assert_eq!(triangle.vertices().len(), 3);
+        assert_eq!(triangle.orientation(), Orientation::CounterClockwise);
+        assert_eq!(triangle.boundary_points(), 12);
+        assert_eq!(triangle.interior_points(), 3);
+        assert_eq!(triangle.locate((2, 2).into()), PointLocation::OnBoundary);
+        assert_eq!(triangle.locate((3, 2).into()), PointLocation::Outside);
+    }
+
+    #[test]
+    fn degenerate_and_from_moves() {
+        assert_eq!(polygon(&[(0, 0), (2, 2), (5, 5)]).orientation(), Orientation::Degenerate);
+
+        let moves = [(Direction::E, 6), (Direction::S, 6), (Direction::W, 2), (Direction::N, 4),
+                     (Direction::W, 2), (Direction::S, 4), (Direction::W, 2), (Direction::N, 6)];
+        assert_eq!(Polygon::from_moves(Point::origin(), moves), notched());
+    }
+}
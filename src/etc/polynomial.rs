@@ -0,0 +1,110 @@
+#![allow(dead_code)]
+
+use num::Integer;
+use num::rational::Ratio;
+use num_traits::{PrimInt, Signed};
+
+/** A polynomial sequence sampled at the consecutive indices 0, 1, 2...,
+    stored as its forward differences at index 0. Since every term of a
+    Newton series with integer samples is an integer, it can be evaluated
+    exactly at any index, including negative ones. */
+// https://en.wikipedia.org/wiki/Newton_polynomial#Newton_forward_divided_difference_formula
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NewtonSeries<T: PrimInt + Signed> {
+    diffs: Vec<T>,
+}
+
+impl<T: PrimInt + Signed> NewtonSeries<T> {
+    /** Builds the series of lowest degree that goes through all the samples */
+    pub fn from_samples(samples: &[T]) -> Self {
+        let mut row = samples.to_vec();
+        let mut diffs = vec![];
+
+        while !row.is_empty() && !row.iter().all(|x| x.is_zero()) {
+            diffs.push(row[0]);
+            row = row.windows(2).map(|w| w[1] - w[0]).collect();
+        }
+
+        Self { diffs }
+    }
+
+    /** The degree of the polynomial, which is 0 for constant sequences
+        (including the one that is all zeros) */
+    pub fn degree(&self) -> usize {
+        self.diffs.len().saturating_sub(1)
+    }
+
+    /** The forward differences of every order at index 0 */
+    pub fn differences(&self) -> &[T] {
+        &self.diffs
+    }
+
+    /** The value of the sequence at any index, computed as the sum of
+        every difference times the binomial coefficient C(n, order) */
+    pub fn value_at(&self, n: T) -> T {
+        let mut binomial = T::one();
+        let mut res = T::zero();
+
+        for (order, &diff) in self.diffs.iter().enumerate() {
+            res = res + diff * binomial;
+
+            // C(n, k + 1) = C(n, k) * (n - k) / (k + 1), which is always exact
+            let k = T::from(order).unwrap();
+            binomial = binomial * (n - k) / (k + T::one());
+        }
+
+        res
+    }
+}
+
+/** Evaluates the polynomial of lowest degree that goes through a set of
+    (x, y) points at another x, exactly. All the x values must be different. */
+// https://en.wikipedia.org/wiki/Lagrange_polynomial
+pub fn lagrange_at<T: PrimInt + Signed + Integer>(points: &[(T, T)], x: T) -> Ratio<T> {
+    points.iter().enumerate()
+        .map(|(i, &(xi, yi))| {
+            points.iter().enumerate()
+                .filter(|&(j, _)| j != i)
+                .fold(Ratio::from_integer(yi), |acc, (_, &(xj, _))| {
+                    assert!(xi != xj, "The x values of the points must be different");
+                    acc * Ratio::new(x - xj, xi - xj)
+                })
+        })
+        .fold(Ratio::from_integer(T::zero()), |acc, term| acc + term)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newton_series() {
+        // Examples from day 09
+        let series = NewtonSeries::from_samples(&[10, 13, 16, 21, 30, 45]);
+        assert_eq!(series.degree(), 3);
+        assert_eq!(series.value_at(6), 68);
+        assert_eq!(series.value_at(-1), 5);
+
+        let zeros = NewtonSeries::from_samples(&[0, 0, 0]);
+        assert_eq!((zeros.degree(), zeros.value_at(100)), (0, 0));
+    }
+
+    #[test]
+    fn lagrange_matches_newton() {
+        let samples = [3i64, -2, 7, 40, 121, 270];
+        let series = NewtonSeries::from_samples(&samples);
+        let points = samples.iter().enumerate().map(|(x, &y)| (x as i64, y)).collect::<Vec<_>>();
+
+        for x in -10..20 {
+            assert_eq!(lagrange_at(&points, x), Ratio::from_integer(series.value_at(x)));
+        }
+    }
+
+    #[test]
+    fn lagrange_with_uneven_points() {
+        // y = x^2 / 2, which isn't an integer at odd x
+        let points = [(0i64, 0), (2, 2), (6, 18)];
+        assert_eq!(lagrange_at(&points, 3), Ratio::new(9, 2));
+        assert_eq!(lagrange_at(&points, -4), Ratio::from_integer(8));
+    }
+}
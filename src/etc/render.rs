@@ -0,0 +1,221 @@
+#![allow(dead_code)]
+
+use std::fmt::{Display, Formatter, Result};
+
+use num_traits::int::PrimInt;
+use rustc_hash::FxHashMap;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+const RESET: &str = "\x1b[0m";
+
+/** A color that can be used both for terminal output and images */
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Rgb(u8, u8, u8),
+}
+
+/** Renders a VecMat to the terminal with optional highlighted cells,
+    rulers, box-drawing characters and cropping. Built with `VecMat::render`,
+    the result is obtained through its `Display` implementation. */
+pub struct GridRenderer<'a, T: Copy> {
+    grid: &'a VecMat<T>,
+    highlights: FxHashMap<(usize, usize), Color>,
+    rulers: bool,
+    box_drawing: bool,
+    viewport: (usize, usize, usize, usize),
+}
+
+impl Color {
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Color::Black => (0, 0, 0),
+            Color::Red => (205, 49, 49),
+            Color::Green => (13, 188, 121),
+            Color::Yellow => (229, 229, 16),
+            Color::Blue => (36, 114, 200),
+            Color::Magenta => (188, 63, 188),
+            Color::Cyan => (17, 168, 205),
+            Color::White => (229, 229, 229),
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+
+    /** The ANSI escape sequence that sets this color (in bold) as the foreground */
+    pub fn ansi_code(&self) -> String {
+        match *self {
+            Color::Black => "\x1b[1;30m".to_owned(),
+            Color::Red => "\x1b[1;31m".to_owned(),
+            Color::Green => "\x1b[1;32m".to_owned(),
+            Color::Yellow => "\x1b[1;33m".to_owned(),
+            Color::Blue => "\x1b[1;34m".to_owned(),
+            Color::Magenta => "\x1b[1;35m".to_owned(),
+            Color::Cyan => "\x1b[1;36m".to_owned(),
+            Color::White => "\x1b[1;37m".to_owned(),
+            Color::Rgb(r, g, b) => format!("\x1b[1;38;2;{r};{g};{b}m"),
+        }
+    }
+}
+
+impl<'a, T: Copy> GridRenderer<'a, T> {
+    pub fn new(grid: &'a VecMat<T>) -> Self {
+        Self {
+            grid,
+            highlights: FxHashMap::default(),
+            rulers: false,
+            box_drawing: false,
+            viewport: (0, 0, grid.width(), grid.height()),
+        }
+    }
+
+    /** Draws the given cells with a color. If a cell is highlighted
+        more than once, the last color applies. */
+    pub fn highlight<I, P>(mut self, positions: P, color: Color) -> Self
+    where I: PrimInt,
+          P: IntoIterator<Item = Coords2D<I>>
+    {
+        for pos in positions.into_iter().filter(|&pos| self.grid.is_in_bounds(pos)) {
+            let key = (pos.x.to_usize().unwrap(), pos.y.to_usize().unwrap());
+            self.highlights.insert(key, color);
+        }
+        self
+    }
+
+    /** Draws all cells whose values satisfy a predicate with a color */
+    pub fn highlight_where<F: Fn(T) -> bool>(self, pred: F, color: Color) -> Self {
+        let positions = self.grid.indexed_iter::<usize>()
+            .filter(|(_, val)| pred(*val))
+            .map(|(pos, _)| pos)
+            .collect::<Vec<_>>();
+        self.highlight(positions, color)
+    }
+
+    /** Shows the column numbers above the grid and the row numbers on its left */
+    pub fn rulers(mut self, show: bool) -> Self {
+        self.rulers = show;
+        self
+    }
+
+    /** Replaces the pipe characters `|-LJ7F` with their box-drawing equivalents */
+    pub fn box_drawing(mut self, enable: bool) -> Self {
+        self.box_drawing = enable;
+        self
+    }
+
+    /** Only renders the `width` x `height` rectangle whose top-left corner is
+        at (x, y). The rectangle is clipped to the bounds of the grid. */
+    pub fn viewport(mut self, x: usize, y: usize, width: usize, height: usize) -> Self {
+        let x = x.min(self.grid.width());
+        let y = y.min(self.grid.height());
+        let width = width.min(self.grid.width() - x);
+        let height = height.min(self.grid.height() - y);
+        self.viewport = (x, y, width, height);
+        self
+    }
+
+    fn write_column_rulers(&self, f: &mut Formatter<'_>, margin: usize) -> Result {
+        let (x0, _, width, _) = self.viewport;
+        let columns = x0 .. x0 + width;
+
+        // Only show the tens when there are enough columns to need them
+        if x0 + width > 10 {
+            write!(f, "{:margin$}", "")?;
+            for x in columns.clone() {
+                if x % 10 == 0 {
+                    write!(f, "{}", (x / 10) % 10)?;
+                } else {
+                    write!(f, " ")?;
+                }
+            }
+            writeln!(f)?;
+        }
+
+        write!(f, "{:margin$}", "")?;
+        for x in columns {
+            write!(f, "{}", x % 10)?;
+        }
+        writeln!(f)
+    }
+}
+
+impl<T: Copy + Display> Display for GridRenderer<'_, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let (x0, y0, width, height) = self.viewport;
+        let margin = if self.rulers { (y0 + height).saturating_sub(1).to_string().len() + 1 } else { 0 };
+
+        if self.rulers {
+            self.write_column_rulers(f, margin)?;
+        }
+
+        for y in y0 .. y0 + height {
+            if self.rulers {
+                write!(f, "{:>w$} ", y, w = margin - 1)?;
+            }
+
+            for x in x0 .. x0 + width {
+                let mut cell = self.grid[(x, y)].to_string();
+                if self.box_drawing {
+                    cell = box_char(&cell).map_or(cell, String::from);
+                }
+
+                match self.highlights.get(&(x, y)) {
+                    Some(color) => write!(f, "{}{cell}{RESET}", color.ansi_code())?,
+                    None => write!(f, "{cell}")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T: Copy> VecMat<T> {
+    /** Starts building a colored representation of the matrix for the terminal */
+    pub fn render(&self) -> GridRenderer<'_, T> {
+        GridRenderer::new(self)
+    }
+}
+
+fn box_char(cell: &str) -> Option<&'static str> {
+    match cell {
+        "|" => Some("│"),
+        "-" => Some("─"),
+        "L" => Some("└"),
+        "J" => Some("┘"),
+        "7" => Some("┐"),
+        "F" => Some("┌"),
+         _  => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rulers_and_viewport() {
+        let grid = VecMat::from_str("abcdefghijkl\nmnopqrstuvwx\nABCDEFGHIJKL");
+        let output = grid.render().rulers(true).viewport(8, 1, 10, 2).to_string();
+        assert_eq!(output, "    1 \n  8901\n1 uvwx\n2 IJKL\n");
+    }
+
+    #[test]
+    fn highlights_and_box_drawing() {
+        let grid = VecMat::from_str("F7\nLJ");
+        let output = grid.render()
+            .box_drawing(true)
+            .highlight([Coords2D::new(1, 1), Coords2D::new(5, 5)], Color::Red)
+            .to_string();
+        assert_eq!(output, format!("┌┐\n└{}┘{RESET}\n", Color::Red.ansi_code()));
+    }
+}
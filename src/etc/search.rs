@@ -0,0 +1,362 @@
+#![allow(dead_code)]
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+
+use num_traits::{ToPrimitive, Zero};
+use rustc_hash::FxHashMap;
+
+/** A search space, described by its starting state, the transitions between
+    states and which states are goals. Every search function in this module
+    works over any type that implements it. */
+pub trait SearchProblem {
+    type State: Clone + Hash + Eq;
+    type Cost: Copy + Ord + Zero;
+
+    fn start(&self) -> Self::State;
+
+    /** The states reachable in one step from another one, along with the
+        cost of every step. Costs are ignored by breadth-first searches. */
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, Self::Cost)>;
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /** An estimation of the cost from a state to the closest goal, used by
+        A* and to prune DFS branches. It must never overestimate that cost. */
+    fn heuristic(&self, _state: &Self::State) -> Self::Cost {
+        Self::Cost::zero()
+    }
+}
+
+/** The cheapest path found from the start to a goal,
+    with both of them included in `states` */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path<S, C> {
+    pub cost: C,
+    pub states: Vec<S>,
+}
+
+/** How much work a search needed */
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SearchStats {
+    /** States whose successors were generated */
+    pub expanded: usize,
+    /** Successors generated, including the ones that were discarded */
+    pub generated: usize,
+    /** Largest amount of states waiting to be expanded at once,
+        or the length of the deepest path for DFS */
+    pub max_frontier: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct SearchResult<S, C> {
+    pub path: Option<Path<S, C>>,
+    pub stats: SearchStats,
+}
+
+impl<S, C: Copy> SearchResult<S, C> {
+    pub fn cost(&self) -> Option<C> {
+        self.path.as_ref().map(|path| path.cost)
+    }
+}
+
+/** Finds the path with the fewest steps to a goal */
+pub fn bfs<P: SearchProblem>(problem: &P) -> SearchResult<P::State, usize> {
+    let mut arena = Arena::new(problem.start(), 0);
+    let mut queue = VecDeque::from([0]);
+    let mut stats = SearchStats::default();
+
+    while let Some(current) = queue.pop_front() {
+        let state = arena.states[current].clone();
+        if problem.is_goal(&state) {
+            return arena.result(current, stats);
+        }
+
+        stats.expanded += 1;
+        let steps = arena.costs[current] + 1;
+
+        for (next, _) in problem.successors(&state) {
+            stats.generated += 1;
+            if let Some(ix) = arena.improve(next, steps, current) {
+                queue.push_back(ix);
+            }
+        }
+
+        stats.max_frontier = stats.max_frontier.max(queue.len());
+    }
+
+    SearchResult { path: None, stats }
+}
+
+/** Finds the cheapest path to a goal, expanding states in order of cost */
+// https://en.wikipedia.org/wiki/Dijkstra's_algorithm
+pub fn dijkstra<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    best_first(problem, |_| P::Cost::zero())
+}
+
+/** Finds the cheapest path to a goal, expanding states in order of their
+    cost plus the heuristic estimation of the cost left */
+// https://en.wikipedia.org/wiki/A*_search_algorithm
+pub fn astar<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    best_first(problem, |state| problem.heuristic(state))
+}
+
+/** Same as `dijkstra`, but using a bucket queue with one list of states per
+    cost instead of a heap. Much faster when costs are small integers, but its
+    memory grows with the cost of the path. */
+// https://en.wikipedia.org/wiki/Bucket_queue
+pub fn dijkstra_buckets<P>(problem: &P) -> SearchResult<P::State, P::Cost>
+where P: SearchProblem,
+      P::Cost: ToPrimitive
+{
+    let bucket_of = |cost: P::Cost| cost.to_usize().expect("Costs must be small non-negative integers");
+
+    let mut arena = Arena::new(problem.start(), P::Cost::zero());
+    let mut buckets: Vec<Vec<usize>> = vec![vec![0]];
+    let mut stats = SearchStats::default();
+    let mut frontier = 1;
+    let mut bucket = 0;
+
+    while bucket < buckets.len() {
+        // Steps of zero cost may add more states to the current bucket
+        while let Some(current) = buckets[bucket].pop() {
+            frontier -= 1;
+            let cost = arena.costs[current];
+
+            // Stale entry, the state was later reached through a cheaper path
+            if bucket_of(cost) != bucket {
+                continue;
+            }
+
+            let state = arena.states[current].clone();
+            if problem.is_goal(&state) {
+                return arena.result(current, stats);
+            }
+
+            stats.expanded += 1;
+
+            for (next, step_cost) in problem.successors(&state) {
+                stats.generated += 1;
+                let next_cost = cost + step_cost;
+                if let Some(ix) = arena.improve(next, next_cost, current) {
+                    let target = bucket_of(next_cost);
+                    if target >= buckets.len() {
+                        buckets.resize_with(target + 1, Vec::new);
+                    }
+                    buckets[target].push(ix);
+                    frontier += 1;
+                }
+            }
+
+            stats.max_frontier = stats.max_frontier.max(frontier);
+        }
+
+        bucket += 1;
+    }
+
+    SearchResult { path: None, stats }
+}
+
+/** Finds the cheapest path to a goal with a depth-first branch and bound
+    search. Branches are pruned when their cost plus the heuristic can't beat
+    the best path found so far, or when they reach a state that was already
+    reached at least as cheaply. Uses little memory besides the current path,
+    but may take exponential time with poor heuristics. */
+pub fn dfs<P: SearchProblem>(problem: &P) -> SearchResult<P::State, P::Cost> {
+    let mut best_seen = FxHashMap::default();
+    let mut best: Option<Path<P::State, P::Cost>> = None;
+    let mut stats = SearchStats::default();
+
+    // The current path, with the cost to reach every state in it
+    // and the successors of each one that are left to explore
+    let mut path = vec![];
+    let mut stack: Vec<(P::Cost, Successors<P>)> = vec![];
+    let mut next = Some((problem.start(), P::Cost::zero()));
+
+    loop {
+        if let Some((state, cost)) = next.take() {
+            let bounded = best.as_ref().is_some_and(|best| cost + problem.heuristic(&state) >= best.cost);
+            let improves = best_seen.get(&state).is_none_or(|&seen| cost < seen);
+
+            if !bounded && improves {
+                best_seen.insert(state.clone(), cost);
+                path.push(state);
+
+                if problem.is_goal(path.last().unwrap()) {
+                    best = Some(Path { cost, states: path.clone() });
+                    path.pop();
+                } else {
+                    let mut successors = problem.successors(path.last().unwrap());
+                    successors.reverse();
+                    stats.expanded += 1;
+                    stats.generated += successors.len();
+                    stack.push((cost, successors));
+                    stats.max_frontier = stats.max_frontier.max(stack.len());
+                }
+            }
+        }
+
+        let Some((cost, successors)) = stack.last_mut() else { break };
+        match successors.pop() {
+            Some((state, step_cost)) => next = Some((state, *cost + step_cost)),
+            None => {
+                stack.pop();
+                path.pop();
+            }
+        }
+    }
+
+    SearchResult { path: best, stats }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+type Successors<P> = Vec<(<P as SearchProblem>::State, <P as SearchProblem>::Cost)>;
+
+// Every state found so far, identified by its position in the vectors,
+// along with its best known cost and the state it was reached from
+struct Arena<S, C> {
+    states: Vec<S>,
+    costs: Vec<C>,
+    parents: Vec<Option<usize>>,
+    index: FxHashMap<S, usize>,
+}
+
+impl<S: Clone + Hash + Eq, C: Copy + Ord> Arena<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        let mut index = FxHashMap::default();
+        index.insert(start.clone(), 0);
+        Self { states: vec![start], costs: vec![cost], parents: vec![None], index }
+    }
+
+    // Records a path to a state if it's the first or the cheapest one,
+    // returning the index of the state in that case
+    fn improve(&mut self, state: S, cost: C, parent: usize) -> Option<usize> {
+        match self.index.get(&state) {
+            Some(&ix) if self.costs[ix] <= cost => None,
+            Some(&ix) => {
+                self.costs[ix] = cost;
+                self.parents[ix] = Some(parent);
+                Some(ix)
+            },
+            None => {
+                let ix = self.states.len();
+                self.index.insert(state.clone(), ix);
+                self.states.push(state);
+                self.costs.push(cost);
+                self.parents.push(Some(parent));
+                Some(ix)
+            }
+        }
+    }
+
+    fn result(&self, goal: usize, stats: SearchStats) -> SearchResult<S, C> {
+        let mut states = vec![];
+        let mut current = Some(goal);
+
+        while let Some(ix) = current {
+            states.push(self.states[ix].clone());
+            current = self.parents[ix];
+        }
+
+        states.reverse();
+        SearchResult { path: Some(Path { cost: self.costs[goal], states }), stats }
+    }
+}
+
+// Shared implementation of Dijkstra and A*, where the priority
+// of every state is its cost plus the given estimation
+fn best_first<P, H>(problem: &P, estimate: H) -> SearchResult<P::State, P::Cost>
+where P: SearchProblem,
+      H: Fn(&P::State) -> P::Cost
+{
+    let start = problem.start();
+    let mut heap = BinaryHeap::from([Reverse((estimate(&start), P::Cost::zero(), 0))]);
+    let mut arena = Arena::new(start, P::Cost::zero());
+    let mut stats = SearchStats::default();
+
+    while let Some(Reverse((_, cost, current))) = heap.pop() {
+        // Stale entry, the state was later reached through a cheaper path
+        if cost > arena.costs[current] {
+            continue;
+        }
+
+        let state = arena.states[current].clone();
+        if problem.is_goal(&state) {
+            return arena.result(current, stats);
+        }
+
+        stats.expanded += 1;
+
+        for (next, step_cost) in problem.successors(&state) {
+            stats.generated += 1;
+            let next_cost = cost + step_cost;
+            let priority = next_cost + estimate(&next);
+            if let Some(ix) = arena.improve(next, next_cost, current) {
+                heap.push(Reverse((priority, next_cost, ix)));
+            }
+        }
+
+        stats.max_frontier = stats.max_frontier.max(heap.len());
+    }
+
+    SearchResult { path: None, stats }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A shortcut from 0 to the goal 4 that costs 10, and a longer
+    // path 0 -> 1 -> 2 -> 3 -> 4 where every step costs 1
+    struct Shortcut;
+
+    impl SearchProblem for Shortcut {
+        type State = u32;
+        type Cost = u32;
+
+        fn start(&self) -> u32 {
+            0
+        }
+
+        fn successors(&self, &state: &u32) -> Vec<(u32, u32)> {
+            match state {
+                0 => vec![(4, 10), (1, 1)],
+                4 => vec![],
+                _ => vec![(state + 1, 1)],
+            }
+        }
+
+        fn is_goal(&self, &state: &u32) -> bool {
+            state == 4
+        }
+
+        fn heuristic(&self, &state: &u32) -> u32 {
+            4 - state
+        }
+    }
+
+    fn assert_valid(path: &Path<u32, impl Copy>) {
+        assert_eq!(path.states.first(), Some(&Shortcut.start()));
+        assert!(Shortcut.is_goal(path.states.last().unwrap()));
+    }
+
+    #[test]
+    fn cost_searches_find_the_cheapest_path() {
+        for result in [dijkstra(&Shortcut), astar(&Shortcut), dijkstra_buckets(&Shortcut), dfs(&Shortcut)] {
+            let path = result.path.unwrap();
+            assert_eq!(path.cost, 4);
+            assert_eq!(path.states, [0, 1, 2, 3, 4]);
+            assert_valid(&path);
+        }
+    }
+
+    #[test]
+    fn bfs_finds_the_fewest_steps() {
+        let path = bfs(&Shortcut).path.unwrap();
+        assert_eq!(path.cost, 1);
+        assert_eq!(path.states, [0, 4]);
+        assert_valid(&path);
+    }
+}
@@ -0,0 +1,225 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::hash::Hash;
+
+use num::iter::range_inclusive;
+use num_traits::int::PrimInt;
+use rustc_hash::FxHashMap;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+/** An unbounded 2D structure that only stores the occupied cells,
+    keeping track of the bounding box that contains all of them */
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T: Copy, I: PrimInt + Hash> {
+    data: FxHashMap<Coords2D<I>, T>,
+    bounds: Option<(Coords2D<I>, Coords2D<I>)>,
+    fill: char,
+}
+
+impl<T: Copy, I: PrimInt + Hash> SparseGrid<T, I> {
+    pub fn new() -> Self {
+        Self { data: FxHashMap::default(), bounds: None, fill: '.' }
+    }
+
+    /** Sets the character used to display empty cells, `.` by default */
+    pub fn with_fill(mut self, fill: char) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    /** Builds a sparse grid from the cells of a matrix that satisfy a predicate */
+    pub fn from_vecmat<F: Fn(T) -> bool>(mat: &VecMat<T>, keep: F) -> Self {
+        mat.indexed_iter::<I>().filter(|(_, val)| keep(*val)).collect()
+    }
+
+    /** Copies the contents of the bounding box into a dense matrix, using
+        the provided value for the empty cells. Also returns the position
+        that corresponds to (0, 0) in the matrix. */
+    pub fn to_vecmat(&self, default: T) -> (VecMat<T>, Coords2D<I>) {
+        let Some((min, _)) = self.bounds else {
+            return (VecMat::new(0, 0, default), Coords2D::origin());
+        };
+
+        let mut mat = VecMat::new(self.width(), self.height(), default);
+        for (&pos, &val) in self.data.iter() {
+            let offset = pos - min;
+            mat[(offset.x.to_usize().unwrap(), offset.y.to_usize().unwrap())] = val;
+        }
+
+        (mat, min)
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn get(&self, pos: Coords2D<I>) -> Option<T> {
+        self.data.get(&pos).copied()
+    }
+
+    pub fn get_or(&self, pos: Coords2D<I>, default: T) -> T {
+        self.get(pos).unwrap_or(default)
+    }
+
+    pub fn contains(&self, pos: Coords2D<I>) -> bool {
+        self.data.contains_key(&pos)
+    }
+
+    pub fn insert(&mut self, pos: Coords2D<I>, val: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                Coords2D::new(min.x.min(pos.x), min.y.min(pos.y)),
+                Coords2D::new(max.x.max(pos.x), max.y.max(pos.y)),
+            ),
+        });
+
+        self.data.insert(pos, val)
+    }
+
+    pub fn remove(&mut self, pos: Coords2D<I>) -> Option<T> {
+        let res = self.data.remove(&pos);
+
+        // The bounding box only needs to be recalculated
+        // if the removed cell was on one of its edges
+        if let (Some(_), Some((min, max))) = (res, self.bounds) {
+            if pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y {
+                self.recalculate_bounds();
+            }
+        }
+
+        res
+    }
+
+    /** Returns the top-left and bottom-right corners of the smallest
+        rectangle containing all cells, or `None` if the grid is empty */
+    pub fn bounds(&self) -> Option<(Coords2D<I>, Coords2D<I>)> {
+        self.bounds
+    }
+
+    /** Width of the bounding box */
+    pub fn width(&self) -> usize {
+        self.bounds.map_or(0, |(min, max)| (max.x - min.x).to_usize().unwrap() + 1)
+    }
+
+    /** Height of the bounding box */
+    pub fn height(&self) -> usize {
+        self.bounds.map_or(0, |(min, max)| (max.y - min.y).to_usize().unwrap() + 1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        self.data.iter().map(|(&pos, &val)| (pos, val))
+    }
+
+    fn recalculate_bounds(&mut self) {
+        let positions = || self.data.keys();
+
+        self.bounds = positions().next().map(|_| (
+            Coords2D::new(positions().map(|p| p.x).min().unwrap(), positions().map(|p| p.y).min().unwrap()),
+            Coords2D::new(positions().map(|p| p.x).max().unwrap(), positions().map(|p| p.y).max().unwrap()),
+        ));
+    }
+}
+
+impl<T: Copy, I: PrimInt + Hash> Default for SparseGrid<T, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy, I: PrimInt + Hash> FromIterator<(Coords2D<I>, T)> for SparseGrid<T, I> {
+    fn from_iter<It: IntoIterator<Item = (Coords2D<I>, T)>>(iter: It) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T: Copy, I: PrimInt + Hash> Extend<(Coords2D<I>, T)> for SparseGrid<T, I> {
+    fn extend<It: IntoIterator<Item = (Coords2D<I>, T)>>(&mut self, iter: It) {
+        for (pos, val) in iter {
+            self.insert(pos, val);
+        }
+    }
+}
+
+impl<T: Copy + Display, I: PrimInt + Hash> Display for SparseGrid<T, I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+
+        for y in range_inclusive(min.y, max.y) {
+            for x in range_inclusive(min.x, max.x) {
+                match self.get(Coords2D::new(x, y)) {
+                    Some(val) => write!(f, "{val}")?,
+                    None => write!(f, "{}", self.fill)?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Pos = Coords2D<i32>;
+
+    #[test]
+    fn removing_edge_cell_shrinks_bounds() {
+        let mut grid: SparseGrid<char, i32> = [(Pos::new(0, 0), 'a'), (Pos::new(2, 1), 'b'), (Pos::new(5, 3), 'c')]
+            .into_iter().collect();
+        assert_eq!(grid.bounds(), Some((Pos::new(0, 0), Pos::new(5, 3))));
+
+        // An inner cell doesn't change the bounds
+        grid.insert(Pos::new(3, 2), 'd');
+        grid.remove(Pos::new(3, 2));
+        assert_eq!(grid.bounds(), Some((Pos::new(0, 0), Pos::new(5, 3))));
+
+        assert_eq!(grid.remove(Pos::new(5, 3)), Some('c'));
+        assert_eq!(grid.bounds(), Some((Pos::new(0, 0), Pos::new(2, 1))));
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+    }
+
+    #[test]
+    fn removing_last_cell_clears_bounds() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Pos::new(-4, 7), 1);
+        assert_eq!(grid.remove(Pos::new(-4, 7)), Some(1));
+
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+    }
+
+    #[test]
+    fn to_vecmat_with_negative_coords() {
+        let grid: SparseGrid<char, i32> = [(Pos::new(-2, -1), '#'), (Pos::new(1, 0), '@')].into_iter().collect();
+        let (mat, offset) = grid.to_vecmat('.');
+
+        assert_eq!(offset, Pos::new(-2, -1));
+        assert_eq!((mat.width(), mat.height()), (4, 2));
+        assert_eq!(mat.get_row(0), vec!['#', '.', '.', '.']);
+        assert_eq!(mat.get_row(1), vec!['.', '.', '.', '@']);
+    }
+
+    #[test]
+    fn display() {
+        let empty: SparseGrid<char, i32> = SparseGrid::new();
+        assert_eq!(empty.to_string(), "");
+
+        let grid: SparseGrid<char, i32> = [(Pos::new(-1, 0), '#'), (Pos::new(0, 1), '#')].into_iter().collect();
+        assert_eq!(grid.with_fill(' ').to_string(), "# \n #\n");
+    }
+}
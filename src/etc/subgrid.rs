@@ -0,0 +1,376 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use num_traits::int::PrimInt;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+/** A read-only rectangular view into a VecMat. All coordinates
+    are relative to the top-left corner of the view. */
+#[derive(Clone, Debug)]
+pub struct SubGrid<'a, T: Copy> {
+    mat: &'a VecMat<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/** A mutable rectangular view into a VecMat. All coordinates
+    are relative to the top-left corner of the view. */
+#[derive(Debug)]
+pub struct SubGridMut<'a, T: Copy> {
+    mat: &'a mut VecMat<T>,
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/** Iterator over all the sub-grids of a given size in a VecMat,
+    in row-major order of their top-left corners. */
+pub struct SubGridWindows<'a, T: Copy> {
+    mat: &'a VecMat<T>,
+    width: usize,
+    height: usize,
+    next_x: usize,
+    next_y: usize,
+}
+
+impl<'a, T: Copy> SubGrid<'a, T> {
+    pub fn new(mat: &'a VecMat<T>, x: usize, y: usize, width: usize, height: usize) -> Self {
+        check_region(mat, x, y, width, height);
+        Self { mat, x, y, width, height }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /** Position of the top-left corner of the view in the parent matrix */
+    pub fn offset<I: PrimInt>(&self) -> Coords2D<I> {
+        (I::from(self.x).unwrap(), I::from(self.y).unwrap()).into()
+    }
+
+    /** Translates a position relative to the view into the parent's coordinates */
+    pub fn to_parent<I: PrimInt>(&self, pos: Coords2D<I>) -> Coords2D<I> {
+        pos + self.offset()
+    }
+
+    pub fn is_in_bounds<I: PrimInt>(&self, pos: Coords2D<I>) -> bool {
+        in_bounds(pos, self.width, self.height)
+    }
+
+    pub fn get_or<I: PrimInt + Display>(&self, pos: Coords2D<I>, default: T) -> T {
+        if self.is_in_bounds(pos) {
+            self[pos]
+        } else {
+            default
+        }
+    }
+
+    pub fn get_row(&self, row: usize) -> Vec<T> {
+        assert!(row < self.height());
+        (0..self.width()).map(|x| self[(x, row)]).collect()
+    }
+
+    pub fn get_col(&self, col: usize) -> Vec<T> {
+        assert!(col < self.width());
+        (0..self.height()).map(|y| self[(col, y)]).collect()
+    }
+
+    pub fn indexed_iter<I: PrimInt + Display>(&self) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        (0..self.height()).flat_map(move |y| (0..self.width()).map(move |x| {
+            let pos = Coords2D::new(I::from(x).unwrap(), I::from(y).unwrap());
+            (pos, self[pos])
+        }))
+    }
+
+    /** Copies the contents of the view into a new, independent matrix */
+    pub fn to_vecmat(&self) -> VecMat<T> {
+        let data = (0..self.height()).flat_map(|y| self.get_row(y)).collect();
+        VecMat::from_data(self.width(), self.height(), data)
+    }
+}
+
+impl<'a, T: Copy> SubGridMut<'a, T> {
+    pub fn new(mat: &'a mut VecMat<T>, x: usize, y: usize, width: usize, height: usize) -> Self {
+        check_region(mat, x, y, width, height);
+        Self { mat, x, y, width, height }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /** Borrows this view as a read-only one */
+    pub fn as_view(&self) -> SubGrid<'_, T> {
+        SubGrid { mat: self.mat, x: self.x, y: self.y, width: self.width, height: self.height }
+    }
+
+    pub fn is_in_bounds<I: PrimInt>(&self, pos: Coords2D<I>) -> bool {
+        in_bounds(pos, self.width, self.height)
+    }
+
+    /** Sets all cells covered by the view to the same value */
+    pub fn fill(&mut self, value: T) {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                self[(x, y)] = value;
+            }
+        }
+    }
+
+    /** Copies the contents of another matrix into this view, with its
+        top-left corner placed at the given offset. Cells that fall outside
+        of the view are ignored. */
+    pub fn paste<I: PrimInt>(&mut self, other: &VecMat<T>, offset: Coords2D<I>) {
+        let offset = Coords2D::new(offset.x.to_i64().unwrap(), offset.y.to_i64().unwrap());
+
+        for (pos, val) in other.indexed_iter::<i64>() {
+            let target = pos + offset;
+            if self.is_in_bounds(target) {
+                self[target] = val;
+            }
+        }
+    }
+}
+
+impl<'a, T: Copy> SubGridWindows<'a, T> {
+    pub fn new(mat: &'a VecMat<T>, width: usize, height: usize) -> Self {
+        assert!(width > 0 && height > 0, "Window dimensions must be positive");
+        Self { mat, width, height, next_x: 0, next_y: 0 }
+    }
+}
+
+impl<'a, T: Copy> Iterator for SubGridWindows<'a, T> {
+    type Item = SubGrid<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.width > self.mat.width() || self.next_y + self.height > self.mat.height() {
+            return None;
+        }
+
+        let res = SubGrid::new(self.mat, self.next_x, self.next_y, self.width, self.height);
+
+        self.next_x += 1;
+        if self.next_x + self.width > self.mat.width() {
+            self.next_x = 0;
+            self.next_y += 1;
+        }
+
+        Some(res)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+
+fn check_region<T: Copy>(mat: &VecMat<T>, x: usize, y: usize, width: usize, height: usize) {
+    assert!(x + width <= mat.width(), "Sub-grid exceeds width: {x}+{width} but width is {}", mat.width());
+    assert!(y + height <= mat.height(), "Sub-grid exceeds height: {y}+{height} but height is {}", mat.height());
+}
+
+fn in_bounds<I: PrimInt>(pos: Coords2D<I>, width: usize, height: usize) -> bool {
+    let x = pos.x.to_i64().unwrap();
+    let y = pos.y.to_i64().unwrap();
+    x >= 0 && y >= 0 && x < width as i64 && y < height as i64
+}
+
+/** Translates view-relative coordinates into parent coordinates,
+    panicking if they fall outside of the view. */
+fn parent_coords<I: PrimInt + Display>(x: I, y: I, view: (usize, usize, usize, usize)) -> (usize, usize) {
+    let (off_x, off_y, width, height) = view;
+    let x = x.to_usize().unwrap_or_else(|| panic!("X index not valid: {x}"));
+    let y = y.to_usize().unwrap_or_else(|| panic!("Y index not valid: {y}"));
+    assert!(x < width, "x index out of bounds: {x} but width is {width}");
+    assert!(y < height, "y index out of bounds: {y} but height is {height}");
+    (off_x + x, off_y + y)
+}
+
+impl<T, I> Index<(I, I)> for SubGrid<'_, T>
+where T: Copy,
+      I: PrimInt + Display
+{
+    type Output = T;
+
+    fn index(&self, (x, y): (I, I)) -> &Self::Output {
+        let pos = parent_coords(x, y, (self.x, self.y, self.width, self.height));
+        &self.mat[pos]
+    }
+}
+
+impl<T, I> Index<Coords2D<I>> for SubGrid<'_, T>
+where T: Copy,
+      I: PrimInt + Display
+{
+    type Output = T;
+
+    fn index(&self, Coords2D { x, y }: Coords2D<I>) -> &Self::Output {
+        &self[(x, y)]
+    }
+}
+
+impl<T, I> Index<(I, I)> for SubGridMut<'_, T>
+where T: Copy,
+      I: PrimInt + Display
+{
+    type Output = T;
+
+    fn index(&self, (x, y): (I, I)) -> &Self::Output {
+        let pos = parent_coords(x, y, (self.x, self.y, self.width, self.height));
+        &self.mat[pos]
+    }
+}
+
+impl<T, I> IndexMut<(I, I)> for SubGridMut<'_, T>
+where T: Copy,
+      I: PrimInt + Display
+{
+    fn index_mut(&mut self, (x, y): (I, I)) -> &mut Self::Output {
+        let pos = parent_coords(x, y, (self.x, self.y, self.width, self.height));
+        &mut self.mat[pos]
+    }
+}
+
+impl<T, I> Index<Coords2D<I>> for SubGridMut<'_, T>
+where T: Copy,
+      I: PrimInt + Display
+{
+    type Output = T;
+
+    fn index(&self, Coords2D { x, y }: Coords2D<I>) -> &Self::Output {
+        &self[(x, y)]
+    }
+}
+
+impl<T, I> IndexMut<Coords2D<I>> for SubGridMut<'_, T>
+where T: Copy,
+      I: PrimInt + Display
+{
+    fn index_mut(&mut self, Coords2D { x, y }: Coords2D<I>) -> &mut Self::Output {
+        &mut self[(x, y)]
+    }
+}
+
+impl<T: Copy + Display> Display for SubGrid<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                write!(f, "{}", self[(x, y)])?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 1 2 3
+    // 4 5 6 7
+    // 8 9 10 11
+    fn numbers() -> VecMat<u32> {
+        VecMat::from_data(4, 3, (0..12).collect())
+    }
+
+    #[test]
+    fn view_uses_relative_coordinates() {
+        let mat = numbers();
+        let view = mat.view(1, 1, 2, 2);
+        assert_eq!(view[(0, 0)], 5);
+        assert_eq!(view[Coords2D::new(1, 1)], 10);
+        assert_eq!(view.to_parent(Coords2D::new(1, 0)), Coords2D::new(2, 1));
+        assert_eq!(view.to_vecmat(), VecMat::from_data(2, 2, vec![5, 6, 9, 10]));
+    }
+
+    #[test]
+    fn out_of_view_cells_are_not_accessible() {
+        let mat = numbers();
+        let view = mat.view(1, 1, 2, 2);
+        assert!(!view.is_in_bounds(Coords2D::new(2, 0)));
+        assert!(!view.is_in_bounds(Coords2D::new(-1, 0)));
+        assert_eq!(view.get_or(Coords2D::new(2, 0), 99), 99);
+        assert_eq!(view.get_or(Coords2D::new(0, -1), 99), 99);
+    }
+
+    #[test]
+    #[should_panic(expected = "x index out of bounds")]
+    fn indexing_past_the_view_panics() {
+        let mat = numbers();
+        // The cell exists in the parent, but not in the view
+        let _ = mat.view(1, 1, 2, 2)[(2, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Sub-grid exceeds width")]
+    fn view_past_the_matrix_panics() {
+        numbers().view(3, 0, 2, 1);
+    }
+
+    #[test]
+    fn view_mut_writes_through_to_the_parent() {
+        let mut mat = numbers();
+        let mut view = mat.view_mut(2, 1, 2, 2);
+        view.fill(0);
+        view[(0, 0)] = 42;
+        assert_eq!(mat.get_row(1), vec![4, 5, 42, 0]);
+        assert_eq!(mat.get_row(2), vec![8, 9, 0, 0]);
+    }
+
+    #[test]
+    fn windows_cover_every_position() {
+        let mat = numbers();
+        let corners: Vec<u32> = mat.windows(3, 2).map(|w| w[(0, 0)]).collect();
+        assert_eq!(corners, vec![0, 1, 4, 5]);
+        assert_eq!(mat.windows(4, 3).count(), 1);
+    }
+
+    #[test]
+    fn windows_larger_than_the_matrix_are_empty() {
+        let mat = numbers();
+        assert_eq!(mat.windows(5, 1).count(), 0);
+        assert_eq!(mat.windows(1, 4).count(), 0);
+    }
+
+    #[test]
+    fn paste_clips_at_every_edge() {
+        let stamp = VecMat::from_data(2, 2, vec![100, 101, 102, 103]);
+
+        let mut mat = numbers();
+        mat.paste(&stamp, Coords2D::new(-1, -1));
+        assert_eq!(mat.get_row(0), vec![103, 1, 2, 3]);
+        assert_eq!(mat.get_row(1), vec![4, 5, 6, 7]);
+
+        let mut mat = numbers();
+        mat.paste(&stamp, Coords2D::new(3, 2));
+        assert_eq!(mat.get_row(2), vec![8, 9, 10, 100]);
+
+        let mut mat = numbers();
+        mat.paste(&stamp, Coords2D::new(10, -5));
+        assert_eq!(mat, numbers());
+    }
+
+    #[test]
+    fn paste_into_a_view_stays_inside_it() {
+        let stamp = VecMat::new(3, 3, 0);
+        let mut mat = numbers();
+        mat.view_mut(1, 1, 2, 1).paste(&stamp, Coords2D::new(-1, 0));
+        assert_eq!(mat.get_row(0), vec![0, 1, 2, 3]);
+        assert_eq!(mat.get_row(1), vec![4, 0, 0, 7]);
+        assert_eq!(mat.get_row(2), vec![8, 9, 10, 11]);
+    }
+}
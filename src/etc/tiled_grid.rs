@@ -0,0 +1,189 @@
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::ops::Index;
+
+use num_traits::int::PrimInt;
+use num_traits::Signed;
+
+use super::coords::Coords2D;
+use super::vecmat::VecMat;
+
+/** How a TiledGrid behaves outside of the bounds of its base tile */
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub enum TileMode<T> {
+    /** The base tile repeats infinitely in every direction */
+    Repeat,
+    /** Everything outside of the base tile has the provided value */
+    Bounded(T),
+}
+
+/** An infinite grid built from a VecMat, which can be
+    indexed using signed coordinates */
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct TiledGrid<T: Copy> {
+    tile: VecMat<T>,
+    mode: TileMode<T>,
+}
+
+impl<T: Copy> TiledGrid<T> {
+    /** Creates a grid from a base tile, which must not be empty */
+    pub fn new(tile: VecMat<T>, mode: TileMode<T>) -> Self {
+        assert!(tile.width() > 0 && tile.height() > 0, "The base tile must not be empty");
+        Self { tile, mode }
+    }
+
+    pub fn repeating(tile: VecMat<T>) -> Self {
+        Self::new(tile, TileMode::Repeat)
+    }
+
+    pub fn bounded(tile: VecMat<T>, default: T) -> Self {
+        Self::new(tile, TileMode::Bounded(default))
+    }
+
+    pub fn tile(&self) -> &VecMat<T> {
+        &self.tile
+    }
+
+    pub fn tile_mut(&mut self) -> &mut VecMat<T> {
+        &mut self.tile
+    }
+
+    pub fn mode(&self) -> TileMode<T> {
+        self.mode
+    }
+
+    pub fn tile_width(&self) -> usize {
+        self.tile.width()
+    }
+
+    pub fn tile_height(&self) -> usize {
+        self.tile.height()
+    }
+
+    /** Returns the coordinates of the tile a position falls into,
+        where (0, 0) is the base tile */
+    pub fn tile_of<I: PrimInt + Signed>(&self, pos: Coords2D<I>) -> Coords2D<I> {
+        let (x, y) = to_i64(pos);
+        let tx = x.div_euclid(self.tile_width() as i64);
+        let ty = y.div_euclid(self.tile_height() as i64);
+        (I::from(tx).unwrap(), I::from(ty).unwrap()).into()
+    }
+
+    /** Translates a position into its equivalent inside the base tile */
+    pub fn local_coords<I: PrimInt + Signed>(&self, pos: Coords2D<I>) -> Coords2D<I> {
+        let (x, y) = to_i64(pos);
+        let lx = x.rem_euclid(self.tile_width() as i64);
+        let ly = y.rem_euclid(self.tile_height() as i64);
+        (I::from(lx).unwrap(), I::from(ly).unwrap()).into()
+    }
+
+    pub fn get<I: PrimInt + Signed + Display>(&self, pos: Coords2D<I>) -> T {
+        self[pos]
+    }
+
+    /** Iterates over the four orthogonal neighbors of a position and their values.
+        Since the grid is infinite, all of them are always yielded. */
+    pub fn neighbors4<'a, I: PrimInt + Signed + Display + 'a>(&'a self, pos: Coords2D<I>) -> impl Iterator<Item = (Coords2D<I>, T)> + 'a {
+        pos.neighbors().into_iter().map(|n| (n, self[n]))
+    }
+
+    /** Iterates over the eight surrounding neighbors of a position and their values.
+        Since the grid is infinite, all of them are always yielded. */
+    pub fn neighbors8<'a, I: PrimInt + Signed + Display + 'a>(&'a self, pos: Coords2D<I>) -> impl Iterator<Item = (Coords2D<I>, T)> + 'a {
+        pos.neighbors_diag().into_iter().map(|n| (n, self[n]))
+    }
+
+    /** Copies a rectangle of the infinite grid into a new VecMat,
+        with its top-left corner at the given position */
+    pub fn to_vecmat<I: PrimInt + Signed + Display>(&self, corner: Coords2D<I>, width: usize, height: usize) -> VecMat<T> {
+        let (cx, cy) = to_i64(corner);
+        let data = (0..height as i64)
+            .flat_map(|y| (0..width as i64).map(move |x| (cx + x, cy + y)))
+            .map(|(x, y)| self[Coords2D::new(x, y)])
+            .collect();
+        VecMat::from_data(width, height, data)
+    }
+}
+
+fn to_i64<I: PrimInt>(pos: Coords2D<I>) -> (i64, i64) {
+    (pos.x.to_i64().unwrap(), pos.y.to_i64().unwrap())
+}
+
+impl<T, I> Index<Coords2D<I>> for TiledGrid<T>
+where T: Copy,
+      I: PrimInt + Signed + Display
+{
+    type Output = T;
+
+    fn index(&self, pos: Coords2D<I>) -> &Self::Output {
+        match &self.mode {
+            TileMode::Bounded(default) if !self.tile.is_in_bounds(pos) => default,
+            _ => &self.tile[self.local_coords(pos)],
+        }
+    }
+}
+
+impl<T, I> Index<(I, I)> for TiledGrid<T>
+where T: Copy,
+      I: PrimInt + Signed + Display
+{
+    type Output = T;
+
+    fn index(&self, (x, y): (I, I)) -> &Self::Output {
+        &self[Coords2D::new(x, y)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ab
+    // cd
+    // ef
+    fn tile() -> VecMat<char> {
+        VecMat::from_str("ab\ncd\nef")
+    }
+
+    #[test]
+    fn repeat_wraps_negative_coordinates() {
+        let grid = TiledGrid::repeating(tile());
+        assert_eq!(grid[(-1, 0)], 'b');
+        assert_eq!(grid[(0, -1)], 'e');
+        assert_eq!(grid[(-2, -3)], 'a');
+        assert_eq!(grid[(-3, -4)], 'f');
+    }
+
+    #[test]
+    fn repeat_on_tile_boundaries() {
+        let grid = TiledGrid::repeating(tile());
+        assert_eq!(grid[(2, 0)], 'a');
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid[(2, 3)], 'a');
+        assert_eq!(grid.tile_of(Coords2D::new(2, 3)), Coords2D::new(1, 1));
+        assert_eq!(grid.tile_of(Coords2D::new(1, 2)), Coords2D::new(0, 0));
+        assert_eq!(grid.tile_of(Coords2D::new(-1, -1)), Coords2D::new(-1, -1));
+        assert_eq!(grid.tile_of(Coords2D::new(-2, -3)), Coords2D::new(-1, -1));
+        assert_eq!(grid.tile_of(Coords2D::new(-3, -4)), Coords2D::new(-2, -2));
+        assert_eq!(grid.local_coords(Coords2D::new(-3, -4)), Coords2D::new(1, 2));
+    }
+
+    #[test]
+    fn bounded_uses_the_default_outside_the_tile() {
+        let grid = TiledGrid::bounded(tile(), '#');
+        assert_eq!(grid[(0, 0)], 'a');
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid[(2, 0)], '#');
+        assert_eq!(grid[(0, 3)], '#');
+        assert_eq!(grid[(-1, 0)], '#');
+        assert_eq!(grid[(0, -1)], '#');
+        assert_eq!(grid.to_vecmat(Coords2D::new(-1, -1), 3, 2), VecMat::from_str("###\n#ab"));
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be empty")]
+    fn empty_tile_panics() {
+        TiledGrid::repeating(VecMat::new(0, 3, '.'));
+    }
+}
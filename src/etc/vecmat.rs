@@ -9,6 +9,12 @@ use std::slice::Iter;
 use num_traits::int::PrimInt;
 
 use super::coords::Coords2D;
+use super::subgrid::{SubGrid, SubGridMut, SubGridWindows};
+
+// Offsets for the orthogonal neighbors, in the same order as `Coords2D::neighbors`
+const OFFSETS_4: [(i64, i64); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+// Offsets for all 8 neighbors, in the same order as `Coords2D::neighbors_diag`
+const OFFSETS_8: [(i64, i64); 8] = [(0, -1), (0, 1), (-1, 0), (1, 0), (-1, -1), (1, -1), (-1, 1), (1, 1)];
 
 /** A 2D-like structure backed by a Vec */
 #[derive(Clone, Debug, Hash, PartialEq, Eq)]
@@ -41,6 +47,12 @@ impl<T: Copy> VecMat<T> {
         Self{ width, height: data.len() / width, data }
     }
 
+    /** Builds a new matrix of the same dimensions by applying
+        a function to every value */
+    pub fn map<U: Copy, F: Fn(T) -> U>(&self, mapper: F) -> VecMat<U> {
+        VecMat::from_data(self.width, self.height, self.data.iter().map(|x| mapper(*x)).collect())
+    }
+
     pub fn width(&self) -> usize {
         self.width
     }
@@ -59,7 +71,7 @@ impl<T: Copy> VecMat<T> {
         (0..self.height()).map(|i| self.data[i * self.width() + col]).collect()
     }
 
-    pub fn indexed_iter<I: PrimInt>(&self) -> VecMaxIndexedIter<T, I> {
+    pub fn indexed_iter<I: PrimInt>(&self) -> VecMaxIndexedIter<'_, T, I> {
         VecMaxIndexedIter::new(self)
     }
 
@@ -81,6 +93,83 @@ impl<T: Copy> VecMat<T> {
         }
     }
 
+    /** Returns a read-only view of the `width` x `height` rectangle
+        whose top-left corner is at (x, y) */
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> SubGrid<'_, T> {
+        SubGrid::new(self, x, y, width, height)
+    }
+
+    /** Returns a mutable view of the `width` x `height` rectangle
+        whose top-left corner is at (x, y) */
+    pub fn view_mut(&mut self, x: usize, y: usize, width: usize, height: usize) -> SubGridMut<'_, T> {
+        SubGridMut::new(self, x, y, width, height)
+    }
+
+    /** Iterates over all the `width` x `height` sub-grids of the matrix */
+    pub fn windows(&self, width: usize, height: usize) -> SubGridWindows<'_, T> {
+        SubGridWindows::new(self, width, height)
+    }
+
+    /** Copies the contents of another matrix into this one, with its
+        top-left corner placed at the given offset. Cells that fall outside
+        of this matrix are ignored. */
+    pub fn paste<I: PrimInt>(&mut self, other: &VecMat<T>, offset: Coords2D<I>) {
+        let (width, height) = (self.width(), self.height());
+        self.view_mut(0, 0, width, height).paste(other, offset);
+    }
+
+    /** Iterates over the in-bounds orthogonal neighbors of a position
+        and their values */
+    pub fn neighbors4<I: PrimInt>(&self, pos: Coords2D<I>) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        self.neighbors_with(pos, &OFFSETS_4, false)
+    }
+
+    /** Iterates over the in-bounds orthogonal and diagonal neighbors
+        of a position and their values */
+    pub fn neighbors8<I: PrimInt>(&self, pos: Coords2D<I>) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        self.neighbors_with(pos, &OFFSETS_8, false)
+    }
+
+    /** Like `neighbors4`, but treating the matrix as a torus, so that
+        neighbors past an edge wrap around to the opposite side. On a torus
+        narrower than 3 cells, every distinct neighbor is yielded only once
+        and the position itself is never considered its own neighbor. */
+    pub fn neighbors4_wrapping<I: PrimInt>(&self, pos: Coords2D<I>) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        self.neighbors_with(pos, &OFFSETS_4, true)
+    }
+
+    /** Like `neighbors8`, but treating the matrix as a torus, so that
+        neighbors past an edge wrap around to the opposite side. Small tori
+        are handled like in `neighbors4_wrapping`. */
+    pub fn neighbors8_wrapping<I: PrimInt>(&self, pos: Coords2D<I>) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        self.neighbors_with(pos, &OFFSETS_8, true)
+    }
+
+    fn neighbors_with<I: PrimInt>(&self, pos: Coords2D<I>, offsets: &'static [(i64, i64)], wrap: bool) -> impl Iterator<Item = (Coords2D<I>, T)> + '_ {
+        let x = pos.x.to_i64().unwrap();
+        let y = pos.y.to_i64().unwrap();
+        let (width, height) = (self.width() as i64, self.height() as i64);
+
+        // An empty matrix has no cells to wrap around to
+        let offsets = if self.data.is_empty() { &[] } else { offsets };
+        let wrapped = move |(dx, dy): (i64, i64)| ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height));
+
+        offsets.iter().enumerate().filter_map(move |(i, &(dx, dy))| {
+            let (nx, ny) = if wrap { wrapped((dx, dy)) } else { (x + dx, y + dy) };
+
+            // Wrapping may land on the same cell more than once in small tori,
+            // which is checked against the previous offsets to avoid allocating
+            let repeated = wrap && ((nx, ny) == (x, y) || offsets[..i].iter().any(|&prev| wrapped(prev) == (nx, ny)));
+            let outside = nx < 0 || ny < 0 || nx >= width || ny >= height;
+            if repeated || outside {
+                return None;
+            }
+
+            let val = self.data[ny as usize * self.width + nx as usize];
+            Some((Coords2D::new(I::from(nx).unwrap(), I::from(ny).unwrap()), val))
+        })
+    }
+
     pub fn rotate_right(&mut self) {
         let mut data = vec![];
 
@@ -195,4 +284,32 @@ impl<T: Copy + Display> Display for VecMat<T> {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapping_neighbors_of_empty_matrix() {
+        let mat: VecMat<u8> = VecMat::new(0, 0, 0);
+        assert_eq!(mat.neighbors4_wrapping(Coords2D::new(0, 0)).count(), 0);
+        assert_eq!(mat.neighbors8_wrapping(Coords2D::new(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn wrapping_neighbors_of_small_torus() {
+        let mat = VecMat::from_data(1, 2, vec!['a', 'b']);
+        let neighbors: Vec<_> = mat.neighbors4_wrapping(Coords2D::new(0, 0)).collect();
+        assert_eq!(neighbors, vec![(Coords2D::new(0, 1), 'b')]);
+
+        let single = VecMat::new(1, 1, 'x');
+        assert_eq!(single.neighbors8_wrapping(Coords2D::new(0, 0)).count(), 0);
+    }
+
+    #[test]
+    fn wrapping_neighbors_of_regular_torus() {
+        let mat = VecMat::new(3, 3, 0);
+        assert_eq!(mat.neighbors8_wrapping(Coords2D::new(0, 0)).count(), 8);
+        assert_eq!(mat.neighbors4(Coords2D::new(0, 0)).count(), 2);
+    }
+}
@@ -1,7 +1,7 @@
 mod days;
 mod etc;
 
-use etc::solution::Solution;
+use etc::Solution;
 use days::{day01, day02, day03, day04, day05,
            day06, day07, day08, day09, day10,
            day11, day12, day13, day14, day15,